@@ -0,0 +1,125 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use kata_game_of_life_rs::common::cell_state::CellState;
+use kata_game_of_life_rs::common::simulation::Simulation;
+use kata_game_of_life_rs::nopointer::universe as np;
+use kata_game_of_life_rs::smartpointers::universe as sp;
+
+const SIZES: [usize; 6] = [10, 32, 128, 256, 512, 2048];
+const DENSITIES: [f64; 3] = [0.1, 0.3, 0.5];
+const SEED: u64 = 42;
+// Every sample runs the same first generations of the soup, before it
+// settles; small universes are sampled many times to be measured at all
+const WINDOW: usize = 16;
+const MIN_DURATION: Duration = Duration::from_millis(500);
+
+struct Measure {
+    engine: &'static str,
+    size: usize,
+    density: f64,
+    ticks: usize,
+    elapsed: Duration,
+}
+
+impl Measure {
+    fn generations_per_second(&self) -> f64 {
+        self.ticks as f64 / self.elapsed.as_secs_f64()
+    }
+
+    fn cells_per_second(&self) -> f64 {
+        self.generations_per_second() * (self.size * self.size) as f64
+    }
+
+    fn csv_header() -> &'static str {
+        "engine,width,height,density,ticks,seconds,generations_per_second,cells_per_second"
+    }
+
+    fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{:.6},{:.2},{:.0}",
+            self.engine,
+            self.size,
+            self.size,
+            self.density,
+            self.ticks,
+            self.elapsed.as_secs_f64(),
+            self.generations_per_second(),
+            self.cells_per_second()
+        )
+    }
+}
+
+fn soup(size: usize, density: f64) -> Vec<Vec<CellState>> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    (0..size)
+        .map(|_| (0..size)
+            .map(|_| if rng.gen_bool(density) { CellState::ALIVE } else { CellState::DEAD })
+            .collect()
+        )
+        .collect()
+}
+
+fn new_simulation(engine: &str, states: Vec<Vec<CellState>>) -> Box<dyn Simulation> {
+    match engine {
+        "np" => Box::new(np::Universe::new_from_cell_states(states)),
+        _ => Box::new(sp::Universe::new_with_defined_states(states)),
+    }
+}
+
+fn measure(engine: &'static str, new_simulation: impl Fn() -> Box<dyn Simulation>, size: usize, density: f64) -> Measure {
+    let mut elapsed = Duration::ZERO;
+    let mut ticks = 0;
+    while ticks == 0 || elapsed < MIN_DURATION {
+        let mut simulation = new_simulation();
+        let start = Instant::now();
+        for _ in 0..WINDOW {
+            simulation.advance();
+        }
+        elapsed += start.elapsed();
+        ticks += WINDOW;
+    }
+    Measure { engine, size, density, ticks, elapsed }
+}
+
+// Takes `--csv FILE` to keep the results, and `--max-size N` for a quicker run
+fn main() {
+    let args = std::env::args().collect::<Vec<String>>();
+    let option = |name: &str| args.iter().position(|arg| arg == name).and_then(|index| args.get(index + 1));
+    let csv_path = option("--csv");
+    let max_size = option("--max-size").and_then(|size| size.parse::<usize>().ok()).unwrap_or(usize::MAX);
+
+    let mut measures = vec![];
+    println!("{:<6}{:>11}{:>9}{:>8}{:>16}{:>16}", "engine", "size", "density", "ticks", "generations/s", "cells/s");
+    for size in SIZES.into_iter().filter(|size| *size <= max_size) {
+        for density in DENSITIES {
+            let states = soup(size, density);
+            for engine in ["np", "sp"] {
+                let measure = measure(engine, || new_simulation(engine, states.clone()), size, density);
+                println!(
+                    "{:<6}{:>11}{:>9}{:>8}{:>16.1}{:>16.0}",
+                    engine,
+                    format!("{}x{}", size, size),
+                    density,
+                    measure.ticks,
+                    measure.generations_per_second(),
+                    measure.cells_per_second()
+                );
+                measures.push(measure);
+            }
+        }
+    }
+
+    if let Some(path) = csv_path {
+        let csv = std::iter::once(Measure::csv_header().to_string())
+            .chain(measures.iter().map(|measure| measure.to_csv()))
+            .collect::<Vec<String>>()
+            .join("\n");
+        if let Err(error) = fs::write(path, csv + "\n") {
+            eprintln!("Could not write {}: {}", path, error);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use kata_game_of_life_rs::common::cell_state::CellState;
+use kata_game_of_life_rs::common::larger_than_life::LargerThanLifeRule;
+use kata_game_of_life_rs::common::neighbourhood::Neighbourhood;
+use kata_game_of_life_rs::common::relative_position::RelativePosition;
+use kata_game_of_life_rs::common::rule::Rule;
+use kata_game_of_life_rs::common::simulation::Simulation;
+use kata_game_of_life_rs::nopointer::universe as np;
+use kata_game_of_life_rs::smartpointers::universe as sp;
+
+const SIZE: usize = 64;
+const TICKS: usize = 10;
+
+// Same rule hiding that it is totalistic, so that universes list every
+// neighbour of every cell as they did before summed-area tables
+#[derive(Debug)]
+struct Scanned(LargerThanLifeRule);
+
+impl Rule for Scanned {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        self.0.next_state(state, neighbours)
+    }
+
+    fn name(&self) -> String {
+        self.0.name()
+    }
+
+    fn neighbourhood(&self) -> Option<Neighbourhood> {
+        self.0.neighbourhood()
+    }
+}
+
+fn time_ticks(simulation: &mut dyn Simulation) -> Duration {
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        simulation.advance();
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let states = np::Universe::new(SIZE, SIZE).states();
+    let bosco: Rc<dyn Rule> = Rc::new(LargerThanLifeRule::bosco());
+    let scanned: Rc<dyn Rule> = Rc::new(Scanned(LargerThanLifeRule::bosco()));
+
+    let mut benchmarks: Vec<(&str, Box<dyn Simulation>)> = vec![
+        ("np, summed-area table", Box::new(np::Universe::new_from_cell_states(states.clone()).with_rule(Rc::clone(&bosco)))),
+        ("np, neighbour scan", Box::new(np::Universe::new_from_cell_states(states.clone()).with_rule(scanned))),
+        ("sp, linked neighbours", Box::new(sp::Universe::new_with_defined_states(states).with_rule(bosco))),
+    ];
+
+    println!("{} ticks of {} on {}x{} cells", TICKS, LargerThanLifeRule::bosco(), SIZE, SIZE);
+    for (name, simulation) in benchmarks.iter_mut() {
+        let elapsed = time_ticks(simulation.as_mut());
+        println!("{:<24}{:>10.2} ms/tick", name, elapsed.as_secs_f64() * 1000.0 / TICKS as f64);
+    }
+}
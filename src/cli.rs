@@ -0,0 +1,267 @@
+use kata_game_of_life_rs::common::neighbourhood::Neighbourhood;
+use kata_game_of_life_rs::common::rule;
+use kata_game_of_life_rs::common::topology::Topology;
+use kata_game_of_life_rs::common::update_mode::UpdateMode;
+use kata_game_of_life_rs::elementary::rule::LinearRule;
+use kata_game_of_life_rs::margolus::rule::BlockRule;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Engine {
+    SmartPointers,
+    NoPointer,
+    Elementary,
+    Margolus,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+    pub engine: Engine,
+    pub width: usize,
+    pub height: usize,
+    pub ticks: usize,
+    pub rule: Option<String>,
+    pub neighbourhood: Neighbourhood,
+    pub topology: Topology,
+    pub update_mode: UpdateMode,
+    pub seed: Option<u64>,
+    pub birth_probability: f64,
+    pub survival_probability: f64,
+    pub pattern: Option<String>,
+    pub stats_csv: Option<String>,
+    pub heat_map: Option<String>,
+    pub space_time: Option<String>,
+    pub show_ages: bool,
+    pub second_order: bool,
+    pub list_patterns: bool,
+    pub verify: bool,
+}
+
+impl Options {
+    pub fn usage() -> String {
+        String::from("Usage: kata-game-of-life-rs [verify] [--engine sp|np|1d|margolus] [--width N] [--height N] [--ticks N] [--rule B3/S23|Wireworld|W30|BBM] [--neighbourhood moore|vonneumann|hexagonal[:RANGE]] [--topology plane|torus|klein|cross|sphere|border:alive|border:dead] [--update sync|random-order|probability:P] [--seed N] [--birth-probability P] [--survival-probability P] [--pattern NAME|FILE.rle] [--list-patterns] [--ages] [--second-order] [--stats-csv FILE|-] [--heatmap FILE.pgm|FILE.png|-] [--space-time FILE.pgm|FILE.png|-]")
+    }
+
+    pub fn parse<I: Iterator<Item=String>>(args: I) -> Result<Options, String> {
+        let mut options = Options {
+            engine: Engine::NoPointer,
+            width: 10,
+            height: 10,
+            ticks: 10,
+            rule: None,
+            neighbourhood: Neighbourhood::default(),
+            topology: Topology::default(),
+            update_mode: UpdateMode::default(),
+            seed: None,
+            birth_probability: 1.0,
+            survival_probability: 1.0,
+            pattern: None,
+            stats_csv: None,
+            heat_map: None,
+            space_time: None,
+            show_ages: false,
+            second_order: false,
+            list_patterns: false,
+            verify: false,
+        };
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--engine" => {
+                    options.engine = match Self::value_of(&arg, args.next())?.as_str() {
+                        "sp" => Engine::SmartPointers,
+                        "np" => Engine::NoPointer,
+                        "1d" => Engine::Elementary,
+                        "margolus" => Engine::Margolus,
+                        other => return Err(format!("Unknown engine '{}', expected sp, np, 1d or margolus", other))
+                    }
+                }
+                "--width" => options.width = Self::dimension_of(&arg, args.next())?,
+                "--height" => options.height = Self::dimension_of(&arg, args.next())?,
+                "--ticks" => options.ticks = Self::number_of(&arg, args.next())?,
+                "--rule" => options.rule = Some(Self::value_of(&arg, args.next())?),
+                "--neighbourhood" => options.neighbourhood = Neighbourhood::parse(&Self::value_of(&arg, args.next())?)?,
+                "--topology" => options.topology = Topology::parse(&Self::value_of(&arg, args.next())?)?,
+                "--update" => options.update_mode = UpdateMode::parse(&Self::value_of(&arg, args.next())?)?,
+                "--seed" => options.seed = Some(Self::number_of(&arg, args.next())? as u64),
+                "--birth-probability" => options.birth_probability = Self::probability_of(&arg, args.next())?,
+                "--survival-probability" => options.survival_probability = Self::probability_of(&arg, args.next())?,
+                "--pattern" => options.pattern = Some(Self::value_of(&arg, args.next())?),
+                "--stats-csv" => options.stats_csv = Some(Self::value_of(&arg, args.next())?),
+                "--heatmap" => options.heat_map = Some(Self::value_of(&arg, args.next())?),
+                "--ages" => options.show_ages = true,
+                "--second-order" => options.second_order = true,
+                "--list-patterns" => options.list_patterns = true,
+                "verify" => options.verify = true,
+                "--space-time" => options.space_time = Some(Self::value_of(&arg, args.next())?),
+                other => return Err(format!("Unknown option '{}'", other))
+            }
+        }
+
+        // Rules are read once the engine is known, one-dimensional and block ones having their own notation
+        match (&options.engine, &options.rule) {
+            (Engine::Elementary, Some(name)) => LinearRule::parse(name).map(|_| ())?,
+            (Engine::Margolus, Some(name)) => BlockRule::parse(name).map(|_| ())?,
+            (_, Some(name)) => rule::from_name(name).map(|_| ())?,
+            (_, None) => {}
+        }
+        options.topology.check_size(options.width, options.height)?;
+        if options.second_order && (options.engine != Engine::NoPointer || options.verify) {
+            return Err(String::from("--second-order is only supported by the np engine"));
+        }
+        if options.verify && matches!(options.engine, Engine::Elementary | Engine::Margolus) {
+            return Err(String::from("verify compares the sp and np engines, which only run two-dimensional rules"));
+        }
+
+        Ok(options)
+    }
+
+    fn value_of(option: &str, value: Option<String>) -> Result<String, String> {
+        value.ok_or(format!("Missing value for {}", option))
+    }
+
+    fn number_of(option: &str, value: Option<String>) -> Result<usize, String> {
+        let value = Self::value_of(option, value)?;
+        value.parse::<usize>().map_err(|_| format!("Invalid value '{}' for {}", value, option))
+    }
+
+    fn probability_of(option: &str, value: Option<String>) -> Result<f64, String> {
+        let value = Self::value_of(option, value)?;
+        match value.parse::<f64>() {
+            Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(probability),
+            _ => Err(format!("Invalid probability '{}' for {}, expected a number between 0 and 1", value, option))
+        }
+    }
+
+    fn dimension_of(option: &str, value: Option<String>) -> Result<usize, String> {
+        match Self::number_of(option, value)? {
+            0 => Err(format!("{} must be at least 1", option)),
+            dimension => Ok(dimension)
+        }
+    }
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn should_use_defaults_without_arguments() {
+        let options = parse(&[]).unwrap();
+
+        assert_eq!(options, Options { engine: Engine::NoPointer, width: 10, height: 10, ticks: 10, rule: None, neighbourhood: Neighbourhood::default(), topology: Topology::default(), update_mode: UpdateMode::default(), seed: None, birth_probability: 1.0, survival_probability: 1.0, pattern: None, stats_csv: None, heat_map: None, space_time: None, show_ages: false, second_order: false, list_patterns: false, verify: false });
+    }
+
+    #[test]
+    fn should_parse_statistics_options() {
+        let options = parse(&["--engine", "sp", "--width", "20", "--height", "5", "--ticks", "100", "--stats-csv", "run.csv"]).unwrap();
+
+        assert_eq!(options, Options { engine: Engine::SmartPointers, width: 20, height: 5, ticks: 100, rule: None, neighbourhood: Neighbourhood::default(), topology: Topology::default(), update_mode: UpdateMode::default(), seed: None, birth_probability: 1.0, survival_probability: 1.0, pattern: None, stats_csv: Some(String::from("run.csv")), heat_map: None, space_time: None, show_ages: false, second_order: false, list_patterns: false, verify: false });
+    }
+
+    #[test]
+    fn should_parse_rule_and_pattern_options() {
+        let options = parse(&["--rule", "Wireworld", "--pattern", "diode.rle"]).unwrap();
+
+        assert_eq!(options.rule, Some(String::from("Wireworld")));
+        assert_eq!(options.pattern, Some(String::from("diode.rle")));
+    }
+
+    #[test]
+    fn should_parse_neighbourhood_option() {
+        let options = parse(&["--neighbourhood", "vonneumann:2"]).unwrap();
+
+        assert_eq!(options.neighbourhood, Neighbourhood::VonNeumann(2));
+    }
+
+    #[test]
+    fn should_read_rules_of_the_selected_engine() {
+        let options = parse(&["--rule", "W110", "--engine", "1d", "--space-time", "-"]).unwrap();
+
+        assert_eq!(options.engine, Engine::Elementary);
+        assert_eq!(options.rule, Some(String::from("W110")));
+        assert_eq!(options.space_time, Some(String::from("-")));
+        assert!(parse(&["--rule", "W110"]).is_err());
+        assert!(parse(&["--engine", "1d", "--rule", "B3/S23"]).is_err());
+    }
+
+    #[test]
+    fn should_parse_verify_command() {
+        let options = parse(&["verify", "--seed", "3", "--ticks", "50"]).unwrap();
+
+        assert!(options.verify);
+        assert_eq!(options.ticks, 50);
+        assert!(parse(&["verify", "--engine", "1d"]).is_err());
+        assert!(parse(&["verify", "--second-order"]).is_err());
+    }
+
+    #[test]
+    fn should_parse_list_patterns_flag() {
+        assert!(parse(&["--list-patterns"]).unwrap().list_patterns);
+        assert_eq!(parse(&["--pattern", "glider"]).unwrap().pattern, Some(String::from("glider")));
+    }
+
+    #[test]
+    fn should_only_run_second_order_with_no_pointer_engine() {
+        assert!(parse(&["--second-order"]).unwrap().second_order);
+        assert!(parse(&["--engine", "sp", "--second-order"]).is_err());
+    }
+
+    #[test]
+    fn should_read_block_rules_with_the_margolus_engine() {
+        let options = parse(&["--engine", "margolus", "--rule", "Critters", "--topology", "torus"]).unwrap();
+
+        assert_eq!(options.engine, Engine::Margolus);
+        assert_eq!(options.rule, Some(String::from("Critters")));
+        assert!(parse(&["--engine", "margolus", "--rule", "B3/S23"]).is_err());
+        assert!(parse(&["--rule", "Critters"]).is_err());
+    }
+
+    #[test]
+    fn should_parse_update_and_noise_options() {
+        let options = parse(&["--update", "random-order", "--seed", "42", "--birth-probability", "0.9", "--survival-probability", "1"]).unwrap();
+
+        assert_eq!(options.update_mode, UpdateMode::RandomOrder);
+        assert_eq!(options.seed, Some(42));
+        assert_eq!((options.birth_probability, options.survival_probability), (0.9, 1.0));
+        assert!(parse(&["--birth-probability", "2"]).is_err());
+        assert!(parse(&["--update", "probability:-1"]).is_err());
+    }
+
+    #[test]
+    fn should_parse_ages_flag() {
+        assert!(parse(&["--ages"]).unwrap().show_ages);
+        assert!(!parse(&[]).unwrap().show_ages);
+    }
+
+    #[test]
+    fn should_parse_topology_option() {
+        let options = parse(&["--topology", "klein"]).unwrap();
+
+        assert_eq!(options.topology, Topology::KleinBottle);
+    }
+
+    #[test]
+    fn should_parse_heat_map_option() {
+        let options = parse(&["--ticks", "50", "--heatmap", "activity.png"]).unwrap();
+
+        assert_eq!(options.ticks, 50);
+        assert_eq!(options.heat_map, Some(String::from("activity.png")));
+    }
+
+    #[test]
+    fn should_reject_unknown_options_and_invalid_values() {
+        assert!(parse(&["--colour"]).is_err());
+        assert!(parse(&["--width", "zero"]).is_err());
+        assert!(parse(&["--width", "0"]).is_err());
+        assert!(parse(&["--stats-csv"]).is_err());
+        assert!(parse(&["--rule", "B3/S9"]).is_err());
+        assert!(parse(&["--neighbourhood", "square"]).is_err());
+        assert!(parse(&["--topology", "cylinder"]).is_err());
+        assert!(parse(&["--topology", "sphere", "--width", "20", "--height", "10"]).is_err());
+    }
+}
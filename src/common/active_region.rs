@@ -0,0 +1,226 @@
+use std::rc::Rc;
+
+use crate::common::cell_state::CellState;
+use crate::common::neighbourhood::Neighbourhood;
+use crate::common::rule::Rule;
+use crate::common::topology::{Location, Topology};
+use crate::common::update_mode::UpdateMode;
+
+const TILE_SIZE: usize = 8;
+
+/// Tiles of a grid whose cells may change at the next generation: the ones
+/// where a cell changed at the previous generation, or which read neighbours
+/// from such a tile. Cells of the other tiles keep their state under any
+/// rule only depending on the neighbourhood, and need not be computed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveRegion {
+    width: usize,
+    height: usize,
+    tiles_across: usize,
+    // Tiles the cells of every tile read their neighbours from, itself included
+    sources: Rc<Vec<Vec<usize>>>,
+    active: Vec<bool>,
+    changed: Vec<bool>,
+}
+
+impl ActiveRegion {
+    /// Region of a grid where every tile is active, nothing being known of the previous generation.
+    pub fn new(width: usize, height: usize, neighbourhood: &Neighbourhood, topology: &Topology) -> ActiveRegion {
+        let tiles_across = width.div_ceil(TILE_SIZE);
+        let tiles_down = height.div_ceil(TILE_SIZE);
+        let range = neighbourhood.range() as i64;
+
+        let sources = (0..tiles_down)
+            .flat_map(|tile_y| (0..tiles_across).map(move |tile_x| (tile_x, tile_y)))
+            .map(|(tile_x, tile_y)| {
+                let min_x = (tile_x * TILE_SIZE) as i64;
+                let min_y = (tile_y * TILE_SIZE) as i64;
+                let max_x = (min_x + TILE_SIZE as i64).min(width as i64) - 1;
+                let max_y = (min_y + TILE_SIZE as i64).min(height as i64) - 1;
+                let inside_tile = |x: i64, y: i64| (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y);
+
+                // Neighbours of the cells of the tile all lie in the tile or the band around it
+                let mut tile_sources = (min_y - range..=max_y + range)
+                    .flat_map(|y| (min_x - range..=max_x + range).map(move |x| (x, y)))
+                    .filter(|(x, y)| !inside_tile(*x, *y))
+                    .filter_map(|(x, y)| match topology.locate(x, y, width, height) {
+                        Location::Cell(x, y) => Some((y / TILE_SIZE) * tiles_across + x / TILE_SIZE),
+                        _ => None,
+                    })
+                    .chain(std::iter::once(tile_y * tiles_across + tile_x))
+                    .collect::<Vec<usize>>();
+                tile_sources.sort_unstable();
+                tile_sources.dedup();
+                tile_sources
+            })
+            .collect::<Vec<Vec<usize>>>();
+
+        let tiles = tiles_across * tiles_down;
+        ActiveRegion {
+            width,
+            height,
+            tiles_across,
+            sources: Rc::new(sources),
+            active: vec![false; tiles],
+            changed: vec![true; tiles],
+        }
+    }
+
+    /// Whether cells can be skipped at all: a cell none of the neighbours of
+    /// which changed can still change under a rule drawing at random, or when
+    /// cells see updates of the same generation or skip some of them.
+    pub fn can_skip(rule: &dyn Rule, update_mode: &UpdateMode) -> bool {
+        *update_mode == UpdateMode::Synchronous && rule.is_deterministic()
+    }
+
+    /// Region of the next generation, following the previous one when there
+    /// is one and starting with every tile active otherwise.
+    pub fn resume(previous: Option<ActiveRegion>, width: usize, height: usize, neighbourhood: &Neighbourhood, topology: &Topology) -> ActiveRegion {
+        let mut region = previous
+            .filter(|previous| (previous.width, previous.height) == (width, height))
+            .unwrap_or_else(|| ActiveRegion::new(width, height, neighbourhood, topology));
+        region.advance();
+        region
+    }
+
+    /// Notes the change of a cell computed at the current generation, if any.
+    pub fn record(&mut self, x: usize, y: usize, state: &CellState, next_state: &CellState) {
+        if state != next_state {
+            self.mark_changed(x, y);
+        }
+    }
+
+    /// Notes a cell changed, during a generation or between two of them.
+    pub fn mark_changed(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            let tile = self.tile_of(x, y);
+            self.changed[tile] = true;
+        }
+    }
+
+    /// Notes every cell may have changed, the next generation being computed in full.
+    pub fn mark_all_changed(&mut self) {
+        self.changed.fill(true);
+    }
+
+    /// Starts a generation, the tiles made active by the changes noted so far
+    /// being the ones to compute.
+    pub fn advance(&mut self) {
+        for (tile, active) in self.active.iter_mut().enumerate() {
+            *active = self.sources[tile].iter().any(|source| self.changed[*source]);
+        }
+        self.changed.fill(false);
+    }
+
+    /// Whether the cell has to be computed at the current generation.
+    pub fn is_active(&self, x: usize, y: usize) -> bool {
+        self.active[self.tile_of(x, y)]
+    }
+
+    pub fn active_tiles(&self) -> usize {
+        self.active.iter().filter(|active| **active).count()
+    }
+
+    pub fn tiles(&self) -> usize {
+        self.active.len()
+    }
+
+    fn tile_of(&self, x: usize, y: usize) -> usize {
+        (y / TILE_SIZE) * self.tiles_across + x / TILE_SIZE
+    }
+}
+
+#[cfg(test)]
+mod active_region_tests {
+    use super::*;
+    use crate::common::rule::LifeLikeRule;
+    use crate::common::stochastic::StochasticRule;
+
+    #[test]
+    fn should_compute_every_tile_at_first() {
+        let mut region = ActiveRegion::new(40, 20, &Neighbourhood::default(), &Topology::Plane);
+
+        region.advance();
+
+        assert_eq!((region.active_tiles(), region.tiles()), (15, 15));
+    }
+
+    #[test]
+    fn should_only_compute_the_tiles_around_a_change() {
+        let mut region = ActiveRegion::new(64, 64, &Neighbourhood::default(), &Topology::Plane);
+        region.advance();
+
+        region.mark_changed(20, 20);
+        region.advance();
+
+        assert_eq!(region.active_tiles(), 9);
+        assert!(region.is_active(8, 31));
+        assert!(!region.is_active(32, 20));
+    }
+
+    #[test]
+    fn should_activate_tiles_across_the_edges_the_topology_joins() {
+        let mut plane = ActiveRegion::new(64, 64, &Neighbourhood::default(), &Topology::Plane);
+        let mut torus = ActiveRegion::new(64, 64, &Neighbourhood::default(), &Topology::Torus);
+        plane.advance();
+        torus.advance();
+
+        plane.mark_changed(0, 0);
+        torus.mark_changed(0, 0);
+        plane.advance();
+        torus.advance();
+
+        assert_eq!((plane.active_tiles(), torus.active_tiles()), (4, 9));
+        assert!(!plane.is_active(63, 63));
+        assert!(torus.is_active(63, 63));
+    }
+
+    #[test]
+    fn should_reach_further_tiles_with_a_larger_range() {
+        let mut region = ActiveRegion::new(64, 64, &Neighbourhood::Moore(10), &Topology::Plane);
+        region.advance();
+
+        region.mark_changed(20, 20);
+        region.advance();
+
+        assert_eq!(region.active_tiles(), 25);
+    }
+
+    #[test]
+    fn should_only_skip_cells_under_deterministic_rules_updated_at_once() {
+        let conway = LifeLikeRule::conway();
+        let noisy = StochasticRule::new(Rc::new(LifeLikeRule::conway()), 0.5, 1.0);
+
+        assert!(ActiveRegion::can_skip(&conway, &UpdateMode::Synchronous));
+        assert!(!ActiveRegion::can_skip(&conway, &UpdateMode::RandomOrder));
+        assert!(!ActiveRegion::can_skip(&conway, &UpdateMode::Probabilistic(0.5)));
+        assert!(!ActiveRegion::can_skip(&noisy, &UpdateMode::Synchronous));
+    }
+
+    #[test]
+    fn should_resume_from_the_changes_recorded_at_the_previous_generation() {
+        let first = ActiveRegion::resume(None, 64, 64, &Neighbourhood::default(), &Topology::Plane);
+        assert_eq!(first.active_tiles(), 64);
+
+        let mut second = ActiveRegion::resume(Some(first), 64, 64, &Neighbourhood::default(), &Topology::Plane);
+        second.record(20, 20, &CellState::DEAD, &CellState::DEAD);
+        second.record(40, 40, &CellState::DEAD, &CellState::ALIVE);
+        let third = ActiveRegion::resume(Some(second), 64, 64, &Neighbourhood::default(), &Topology::Plane);
+
+        assert_eq!(third.active_tiles(), 9);
+        assert!(third.is_active(40, 40));
+        assert!(!third.is_active(20, 20));
+    }
+
+    #[test]
+    fn should_compute_every_tile_after_being_told_everything_changed() {
+        let mut region = ActiveRegion::new(64, 64, &Neighbourhood::default(), &Topology::Plane);
+        region.advance();
+        region.advance();
+
+        region.mark_all_changed();
+        region.advance();
+
+        assert_eq!(region.active_tiles(), 64);
+    }
+}
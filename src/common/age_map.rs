@@ -0,0 +1,130 @@
+use std::io;
+
+use crate::common::cell_age::CellAge;
+
+// Generations after which alive cells are drawn as old, and dead ones no
+// longer leave a trail
+const OLD_AGE: usize = 32;
+const TRAIL_LENGTH: usize = 8;
+
+/// Rendering of the ages of the cells of a universe: newborn cells in
+/// yellow turning red as they get older, and cells that died recently
+/// leaving a blue trail that fades out.
+#[derive(Debug, PartialEq)]
+pub struct AgeMap {
+    ages: Vec<Vec<CellAge>>,
+}
+
+impl AgeMap {
+    pub fn new(ages: Vec<Vec<CellAge>>) -> AgeMap {
+        AgeMap { ages }
+    }
+
+    pub fn width(&self) -> usize {
+        self.ages.first().map_or(0, |line| line.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.ages.len()
+    }
+
+    /// Colour of a cell, none for dead cells whose trail faded out. Ages of 0,
+    /// which no universe reaches, are coloured as ages of 1.
+    pub fn colour_of(age: &CellAge) -> Option<(u8, u8, u8)> {
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match age {
+            CellAge::Alive(generations) => {
+                let oldness = generations.saturating_sub(1) as f64 / (OLD_AGE - 1) as f64;
+                Some((255, channel(1.0 - oldness), 0))
+            }
+            CellAge::Dead(generations) if *generations <= TRAIL_LENGTH => {
+                let fading = generations.saturating_sub(1) as f64 / TRAIL_LENGTH as f64;
+                Some((0, 0, channel(1.0 - fading)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Lines of cells as `x` (alive) and `.` (trail) coloured by age, other dead cells left as `o`.
+    pub fn print(&self) -> Vec<String> {
+        self
+            .ages
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|age| {
+                    let symbol = match age {
+                        CellAge::Alive(_) => "x",
+                        _ => ".",
+                    };
+                    match Self::colour_of(age) {
+                        Some((red, green, blue)) => format!("\x1b[38;2;{};{};{}m{}\x1b[0m", red, green, blue, symbol),
+                        None => String::from("o"),
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+            )
+            .collect()
+    }
+
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut image: Vec<u8> = vec![];
+        {
+            let mut encoder = png::Encoder::new(&mut image, self.width() as u32, self.height() as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+
+            let pixels = self
+                .ages
+                .iter()
+                .flatten()
+                .flat_map(|age| {
+                    let (red, green, blue) = Self::colour_of(age).unwrap_or((0, 0, 0));
+                    [red, green, blue]
+                })
+                .collect::<Vec<u8>>();
+            writer.write_image_data(&pixels)?;
+        }
+        Ok(image)
+    }
+}
+
+#[cfg(test)]
+mod age_map_tests {
+    use super::*;
+
+    #[test]
+    fn should_colour_young_and_old_cells_differently() {
+        assert_eq!(AgeMap::colour_of(&CellAge::Alive(1)), Some((255, 255, 0)));
+        assert_eq!(AgeMap::colour_of(&CellAge::Alive(OLD_AGE)), Some((255, 0, 0)));
+        assert_eq!(AgeMap::colour_of(&CellAge::Alive(OLD_AGE * 2)), Some((255, 0, 0)));
+    }
+
+    #[test]
+    fn should_fade_trails_of_dead_cells() {
+        assert_eq!(AgeMap::colour_of(&CellAge::Dead(1)), Some((0, 0, 255)));
+        assert!(AgeMap::colour_of(&CellAge::Dead(TRAIL_LENGTH)).unwrap().2 < 255);
+        assert_eq!(AgeMap::colour_of(&CellAge::Dead(TRAIL_LENGTH + 1)), None);
+        assert_eq!(AgeMap::colour_of(&CellAge::NeverAlive), None);
+    }
+
+    #[test]
+    fn should_colour_ages_of_zero_as_ages_of_one() {
+        assert_eq!(AgeMap::colour_of(&CellAge::Alive(0)), AgeMap::colour_of(&CellAge::Alive(1)));
+        assert_eq!(AgeMap::colour_of(&CellAge::Dead(0)), AgeMap::colour_of(&CellAge::Dead(1)));
+    }
+
+    #[test]
+    fn should_print_trails_and_plain_dead_cells() {
+        let age_map = AgeMap::new(vec![vec![CellAge::Alive(1), CellAge::Dead(2), CellAge::NeverAlive]]);
+
+        let line = &age_map.print()[0];
+
+        assert!(line.starts_with("\x1b[38;2;255;255;0mx\x1b[0m "));
+        assert!(line.contains("m.\x1b[0m"));
+        assert!(line.ends_with(" o"));
+        assert_eq!(&age_map.to_png().unwrap()[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
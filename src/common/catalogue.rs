@@ -0,0 +1,156 @@
+use std::fmt;
+
+use crate::common::pattern::Pattern;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PatternKind {
+    StillLife,
+    Oscillator,
+    Spaceship,
+    /// Small pattern taking many generations to settle down.
+    Methuselah,
+    /// Oscillator sending out spaceships.
+    Gun,
+}
+
+impl fmt::Display for PatternKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternKind::StillLife => write!(f, "still life"),
+            PatternKind::Oscillator => write!(f, "oscillator"),
+            PatternKind::Spaceship => write!(f, "spaceship"),
+            PatternKind::Methuselah => write!(f, "methuselah"),
+            PatternKind::Gun => write!(f, "gun"),
+        }
+    }
+}
+
+/// Named pattern of the catalogue: after `period` generations it comes back
+/// as it was, moved by `displacement` cells for spaceships (y growing
+/// downwards), while methuselahs settle down after `lifespan` generations.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatalogueEntry {
+    pub name: &'static str,
+    pub kind: PatternKind,
+    pub period: Option<usize>,
+    pub displacement: (i64, i64),
+    pub lifespan: Option<usize>,
+    rle: &'static str,
+}
+
+impl CatalogueEntry {
+    pub fn pattern(&self) -> Pattern {
+        Pattern::from_rle(self.rle).unwrap()
+    }
+
+    pub fn rule(&self) -> String {
+        self.pattern().rule().unwrap_or("B3/S23").to_string()
+    }
+}
+
+const fn periodic(name: &'static str, kind: PatternKind, period: usize, displacement: (i64, i64), rle: &'static str) -> CatalogueEntry {
+    CatalogueEntry { name, kind, period: Some(period), displacement, lifespan: None, rle }
+}
+
+const fn methuselah(name: &'static str, lifespan: usize, rle: &'static str) -> CatalogueEntry {
+    CatalogueEntry { name, kind: PatternKind::Methuselah, period: None, displacement: (0, 0), lifespan: Some(lifespan), rle }
+}
+
+static CATALOGUE: [CatalogueEntry; 17] = [
+    periodic("block", PatternKind::StillLife, 1, (0, 0), "x = 2, y = 2, rule = B3/S23\n2o$2o!"),
+    periodic("beehive", PatternKind::StillLife, 1, (0, 0), "x = 4, y = 3, rule = B3/S23\nb2o$o2bo$b2o!"),
+    periodic("loaf", PatternKind::StillLife, 1, (0, 0), "x = 4, y = 4, rule = B3/S23\nb2o$o2bo$bobo$2bo!"),
+    periodic("boat", PatternKind::StillLife, 1, (0, 0), "x = 3, y = 3, rule = B3/S23\n2o$obo$bo!"),
+    periodic("blinker", PatternKind::Oscillator, 2, (0, 0), "x = 3, y = 1, rule = B3/S23\n3o!"),
+    periodic("toad", PatternKind::Oscillator, 2, (0, 0), "x = 4, y = 2, rule = B3/S23\nb3o$3o!"),
+    periodic("beacon", PatternKind::Oscillator, 2, (0, 0), "x = 4, y = 4, rule = B3/S23\n2o$2o$2b2o$2b2o!"),
+    periodic("pulsar", PatternKind::Oscillator, 3, (0, 0), "x = 13, y = 13, rule = B3/S23\n2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!"),
+    periodic("pentadecathlon", PatternKind::Oscillator, 15, (0, 0), "x = 10, y = 3, rule = B3/S23\n2bo4bo$2ob4ob2o$2bo4bo!"),
+    periodic("glider", PatternKind::Spaceship, 4, (1, 1), "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!"),
+    periodic("lwss", PatternKind::Spaceship, 4, (-2, 0), "x = 5, y = 4, rule = B3/S23\nbo2bo$o$o3bo$4o!"),
+    periodic("mwss", PatternKind::Spaceship, 4, (-2, 0), "x = 6, y = 5, rule = B3/S23\n3bo$bo3bo$o$o4bo$5o!"),
+    periodic("hwss", PatternKind::Spaceship, 4, (-2, 0), "x = 7, y = 5, rule = B3/S23\n3b2o$bo4bo$o$o5bo$6o!"),
+    methuselah("r-pentomino", 1103, "x = 3, y = 3, rule = B3/S23\nb2o$2o$bo!"),
+    methuselah("acorn", 5206, "x = 7, y = 3, rule = B3/S23\nbo$3bo$2o2b3o!"),
+    methuselah("diehard", 130, "x = 8, y = 3, rule = B3/S23\n6bo$2o$bo3b3o!"),
+    periodic("gosper-glider-gun", PatternKind::Gun, 30, (0, 0), "x = 36, y = 9, rule = B3/S23\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!"),
+];
+
+pub fn entries() -> &'static [CatalogueEntry] {
+    &CATALOGUE
+}
+
+/// Entry of the given name, whatever its case, with spaces or underscores instead of dashes.
+pub fn find(name: &str) -> Option<&'static CatalogueEntry> {
+    let name = name.trim().to_ascii_lowercase().replace([' ', '_'], "-");
+    CATALOGUE.iter().find(|entry| entry.name == name)
+}
+
+/// Pattern of the given name, the error listing the known ones.
+pub fn pattern(name: &str) -> Result<Pattern, String> {
+    find(name).map(|entry| entry.pattern()).ok_or_else(|| format!(
+        "Unknown pattern '{}', expected one of {}",
+        name,
+        CATALOGUE.iter().map(|entry| entry.name).collect::<Vec<&str>>().join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod catalogue_tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::nopointer::universe::Universe;
+
+    // Alive cells relative to the top-left corner of the area they cover
+    fn alive_cells(universe: &Universe) -> BTreeSet<(i64, i64)> {
+        universe.iter_live().map(|(x, y, _)| (x as i64, y as i64)).collect()
+    }
+
+    #[test]
+    fn should_find_patterns_whatever_the_case() {
+        assert_eq!(find("Gosper glider_gun").map(|entry| entry.name), Some("gosper-glider-gun"));
+        assert_eq!(pattern("LWSS").unwrap().width(), 5);
+        assert!(pattern("unicorn").is_err());
+    }
+
+    #[test]
+    fn should_read_every_entry() {
+        for entry in entries() {
+            let pattern = entry.pattern();
+
+            assert!(pattern.width() > 0, "{}", entry.name);
+            assert_eq!(entry.rule(), "B3/S23");
+        }
+    }
+
+    #[test]
+    fn should_come_back_moved_by_the_displacement_after_a_period() {
+        for entry in entries().iter().filter(|entry| matches!(entry.kind, PatternKind::StillLife | PatternKind::Oscillator | PatternKind::Spaceship)) {
+            let period = entry.period.unwrap();
+            let mut universe = Universe::new_from_cell_states(entry.pattern().centered_in(30, 30));
+            let before = alive_cells(&universe);
+
+            for _ in 0..period {
+                universe = universe.tick();
+            }
+
+            let (dx, dy) = entry.displacement;
+            let moved_back = alive_cells(&universe).into_iter().map(|(x, y)| (x - dx, y - dy)).collect::<BTreeSet<(i64, i64)>>();
+            assert_eq!(moved_back, before, "{}", entry.name);
+        }
+    }
+
+    #[test]
+    fn should_send_a_glider_every_period_from_the_gun() {
+        let gun = find("gosper-glider-gun").unwrap();
+        let mut universe = Universe::new_from_cell_states(gun.pattern().centered_in(60, 40));
+        let population = universe.statistics().population;
+
+        for _ in 0..gun.period.unwrap() {
+            universe = universe.tick();
+        }
+
+        assert_eq!(universe.statistics().population, population + 5);
+    }
+}
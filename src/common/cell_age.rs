@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::cell_state::CellState;
+
+/// How long a cell has been alive, or dead since it last was, in generations.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum CellAge {
+    /// Alive for the given number of generations, 1 being the one it was born in.
+    Alive(usize),
+    /// Dead for the given number of generations, 1 being the one it died in.
+    Dead(usize),
+    /// Dead since the start of the run.
+    NeverAlive,
+}
+
+impl CellAge {
+    /// Age of a cell in the given state when ages start being tracked.
+    pub fn of(state: &CellState) -> CellAge {
+        match state.is_alive() {
+            true => CellAge::Alive(1),
+            false => CellAge::NeverAlive,
+        }
+    }
+
+    /// Age of the cell one generation later, in the given state.
+    pub fn next(&self, state: &CellState) -> CellAge {
+        match (self, state.is_alive()) {
+            (CellAge::Alive(generations), true) => CellAge::Alive(generations + 1),
+            (CellAge::Alive(_), false) => CellAge::Dead(1),
+            (CellAge::Dead(generations), false) => CellAge::Dead(generations + 1),
+            (CellAge::NeverAlive, false) => CellAge::NeverAlive,
+            (_, true) => CellAge::Alive(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cell_age_tests {
+    use super::*;
+
+    #[test]
+    fn should_count_generations_alive_then_dead() {
+        let states = [CellState::ALIVE, CellState::ALIVE, CellState::DYING(1), CellState::DEAD, CellState::ALIVE];
+        let mut age = CellAge::of(&CellState::DEAD);
+        let mut ages = vec![];
+
+        for state in &states {
+            age = age.next(state);
+            ages.push(age);
+        }
+
+        assert_eq!(ages, vec![CellAge::Alive(1), CellAge::Alive(2), CellAge::Dead(1), CellAge::Dead(2), CellAge::Alive(1)]);
+    }
+
+    #[test]
+    fn should_stay_never_alive_until_born() {
+        assert_eq!(CellAge::of(&CellState::DEAD).next(&CellState::DEAD), CellAge::NeverAlive);
+        assert_eq!(CellAge::of(&CellState::ALIVE), CellAge::Alive(1));
+    }
+}
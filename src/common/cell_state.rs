@@ -1,6 +1,166 @@
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Clone)]
+/// Last refractory stage a cell can be printed in with a single character,
+/// from `1` to `9` then `A` to `Z`.
+pub const MAX_DYING_STAGE: u8 = 35;
+
+/// Last colour an alive cell can be printed in with a single character,
+/// from `b` to `n`, `x` standing for the first one.
+pub const MAX_COLOUR: u8 = 14;
+
+/// Last state of a multi-state rule that can be printed with a single
+/// character, from `p` to `w`.
+pub const MAX_STATE: u8 = 8;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum CellState {
     ALIVE,
     DEAD,
+    /// Refractory state of Generations rules: a cell that stopped surviving
+    /// goes through `DYING(1)`, `DYING(2)`, ... before being dead again.
+    DYING(u8),
+    /// Alive cell of a multi-colour rule such as Immigration or QuadLife, with
+    /// colours numbered from 2 as `ALIVE` stands for the first one.
+    COLOURED(u8),
+    /// Non-empty state of a multi-state rule such as Wireworld, numbered from
+    /// 1 as in Golly, whose meaning only the rule knows. Shared code counts
+    /// these cells alive, as they are not empty.
+    STATE(u8),
+}
+
+impl CellState {
+    pub fn is_alive(&self) -> bool {
+        matches!(self, CellState::ALIVE | CellState::COLOURED(_) | CellState::STATE(_))
+    }
+
+    /// Colour of an alive cell, from 1.
+    pub fn colour(&self) -> Option<u8> {
+        match self {
+            CellState::ALIVE => Some(1),
+            CellState::COLOURED(colour) => Some(*colour),
+            _ => None,
+        }
+    }
+
+    /// Dead cell for an alive one and alive cell otherwise, dying ones included.
+    pub fn toggled(&self) -> CellState {
+        match self.is_alive() {
+            true => CellState::DEAD,
+            false => CellState::ALIVE,
+        }
+    }
+
+    pub fn of_colour(colour: u8) -> CellState {
+        match colour {
+            1 => CellState::ALIVE,
+            colour => CellState::COLOURED(colour),
+        }
+    }
+
+    pub fn print(&self) -> String {
+        match self {
+            CellState::ALIVE => String::from("x"),
+            CellState::DEAD => String::from("o"),
+            CellState::DYING(stage) => char::from_digit(*stage as u32, 36).map_or(String::from("?"), |digit| digit.to_ascii_uppercase().to_string()),
+            CellState::COLOURED(colour) if (2..=MAX_COLOUR).contains(colour) => ((b'a' + colour - 1) as char).to_string(),
+            CellState::COLOURED(_) => String::from("?"),
+            CellState::STATE(state) if (1..=MAX_STATE).contains(state) => ((b'p' + state - 1) as char).to_string(),
+            CellState::STATE(_) => String::from("?"),
+        }
+    }
+
+    /// State number as in Golly: 0 dead, 1 alive, 2 and above dying, or
+    /// alive in another colour for multi-colour rules, or the state itself
+    /// for other multi-state rules.
+    pub fn index(&self) -> u8 {
+        match self {
+            CellState::DEAD => 0,
+            CellState::ALIVE => 1,
+            CellState::DYING(stage) => stage + 1,
+            CellState::COLOURED(colour) => *colour,
+            CellState::STATE(state) => *state,
+        }
+    }
+
+    pub fn from_index(index: u8) -> CellState {
+        match index {
+            0 => CellState::DEAD,
+            1 => CellState::ALIVE,
+            n => CellState::DYING(n - 1),
+        }
+    }
+
+    pub fn from_char(state: char) -> CellState {
+        match state {
+            'x' => CellState::ALIVE,
+            '1'..='9' | 'A'..='Z' => CellState::DYING(state.to_digit(36).unwrap_or_default() as u8),
+            'b'..='n' => CellState::COLOURED(state as u8 - b'a' + 1),
+            'p'..='w' => CellState::STATE(state as u8 - b'p' + 1),
+            _ => CellState::DEAD
+        }
+    }
+}
+
+#[cfg(test)]
+mod cell_state_tests {
+    use super::*;
+
+    #[test]
+    fn should_print_and_read_back_every_state() {
+        for state in [CellState::ALIVE, CellState::DEAD, CellState::DYING(1), CellState::DYING(7), CellState::DYING(10), CellState::DYING(MAX_DYING_STAGE), CellState::COLOURED(2), CellState::COLOURED(4), CellState::STATE(1), CellState::STATE(MAX_STATE)] {
+            let printed = state.print();
+
+            assert_eq!(printed.chars().count(), 1);
+            assert_eq!(CellState::from_char(printed.chars().next().unwrap()), state);
+        }
+    }
+
+    #[test]
+    fn should_read_back_every_printable_colour() {
+        for colour in 2..=MAX_COLOUR {
+            let state = CellState::COLOURED(colour);
+
+            assert_eq!(CellState::from_char(state.print().chars().next().unwrap()), state);
+        }
+        assert_eq!(CellState::COLOURED(0).print(), "?");
+        assert_eq!(CellState::COLOURED(MAX_COLOUR + 1).print(), "?");
+        assert_eq!(CellState::COLOURED(u8::MAX).print(), "?");
+    }
+
+    #[test]
+    fn should_number_states_as_golly() {
+        assert_eq!(CellState::DEAD.index(), 0);
+        assert_eq!(CellState::ALIVE.index(), 1);
+        assert_eq!(CellState::DYING(1).index(), 2);
+        assert_eq!(CellState::from_index(3), CellState::DYING(2));
+    }
+
+    #[test]
+    fn should_not_consider_dying_cells_alive() {
+        assert!(CellState::ALIVE.is_alive());
+        assert!(!CellState::DYING(1).is_alive());
+        assert!(!CellState::DEAD.is_alive());
+    }
+
+    #[test]
+    fn should_consider_cells_of_every_colour_alive() {
+        assert!(CellState::COLOURED(3).is_alive());
+        assert_eq!(CellState::of_colour(1), CellState::ALIVE);
+        assert_eq!(CellState::of_colour(3).colour(), Some(3));
+        assert_eq!(CellState::DYING(1).colour(), None);
+    }
+
+    #[test]
+    fn should_consider_cells_in_any_state_of_a_multi_state_rule_alive() {
+        assert!(CellState::STATE(3).is_alive());
+        assert_eq!(CellState::STATE(3).index(), 3);
+        assert_eq!(CellState::STATE(3).colour(), None);
+    }
+
+    #[test]
+    fn should_toggle_between_alive_and_dead() {
+        assert_eq!(CellState::COLOURED(2).toggled(), CellState::DEAD);
+        assert_eq!(CellState::DYING(1).toggled(), CellState::ALIVE);
+        assert_eq!(CellState::DEAD.toggled(), CellState::ALIVE);
+    }
 }
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use crate::common::cell_state::CellState;
+use crate::common::simulation::Simulation;
+
+/// First cell on which the engines disagreed, with the state every engine gave it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Divergence {
+    pub generation: usize,
+    pub x: usize,
+    pub y: usize,
+    pub states: Vec<(String, Option<CellState>)>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states = self
+            .states
+            .iter()
+            .map(|(engine, state)| format!("{} {}", engine, state.as_ref().map_or(String::from("outside"), |state| state.print())))
+            .collect::<Vec<String>>()
+            .join(", ");
+        write!(f, "Engines diverge at generation {}, cell ({}, {}): {}", self.generation, self.x, self.y, states)
+    }
+}
+
+/// Runs the same universe on several engines side by side, comparing them
+/// cell by cell after every generation.
+#[derive(Default)]
+pub struct DifferentialRun {
+    simulations: Vec<(String, Box<dyn Simulation>)>,
+}
+
+impl DifferentialRun {
+    pub fn new() -> DifferentialRun {
+        DifferentialRun::default()
+    }
+
+    pub fn with_engine(mut self, name: &str, simulation: Box<dyn Simulation>) -> DifferentialRun {
+        self.simulations.push((name.to_string(), simulation));
+        self
+    }
+
+    /// First divergence from the starting generation up to the given number of ticks, if any.
+    pub fn run(&mut self, ticks: usize) -> Option<Divergence> {
+        for generation in 0..=ticks {
+            if generation > 0 {
+                self.simulations.iter_mut().for_each(|(_, simulation)| simulation.advance());
+            }
+            if let Some(divergence) = self.divergence(generation) {
+                return Some(divergence);
+            }
+        }
+        None
+    }
+
+    fn divergence(&self, generation: usize) -> Option<Divergence> {
+        let states = self.simulations.iter().map(|(_, simulation)| simulation.states()).collect::<Vec<Vec<Vec<CellState>>>>();
+        let height = states.iter().map(|states| states.len()).max().unwrap_or(0);
+        let width = states.iter().flatten().map(|line| line.len()).max().unwrap_or(0);
+
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| (x, y, states.iter().map(|states| states.get(y).and_then(|line| line.get(x)).cloned()).collect::<Vec<Option<CellState>>>()))
+            .find(|(_, _, cell_states)| cell_states.windows(2).any(|pair| pair[0] != pair[1]))
+            .map(|(x, y, cell_states)| Divergence {
+                generation,
+                x,
+                y,
+                states: self.simulations.iter().map(|(engine, _)| engine.clone()).zip(cell_states).collect(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod differential_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::common::neighbourhood::Neighbourhood;
+    use crate::common::rule::{self, LifeLikeRule};
+    use crate::common::topology::Topology;
+    use crate::common::update_mode::UpdateMode;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    // Both engines on the same seeded soup, under rules, neighbourhoods and topologies of every kind
+    fn seeded_soups(size: usize) -> Vec<(&'static str, np::Universe, sp::Universe)> {
+        let setups = [
+            ("B3/S23", Neighbourhood::default(), Topology::Plane),
+            ("B3/S23", Neighbourhood::default(), Topology::KleinBottle),
+            ("B36/S23", Neighbourhood::VonNeumann(2), Topology::Torus),
+            ("B2/S/C3", Neighbourhood::Hexagonal, Topology::Bordered(CellState::ALIVE)),
+            ("R2,C0,M1,S6..12,B7..9,NM", Neighbourhood::default(), Topology::Sphere),
+            ("B2-a/S12", Neighbourhood::default(), Topology::CrossSurface),
+        ];
+
+        setups
+            .into_iter()
+            .enumerate()
+            .map(|(seed, (rule_name, neighbourhood, topology))| {
+                let np_universe = np::Universe::new_with_seed(size, size, seed as u64).with_neighbourhood(neighbourhood.clone()).with_rule(rule::from_name(rule_name).unwrap()).with_topology(topology.clone());
+                let sp_universe = sp::Universe::new_with_seed(size, size, seed as u64).with_neighbourhood(neighbourhood).with_rule(rule::from_name(rule_name).unwrap()).with_topology(topology);
+                (rule_name, np_universe, sp_universe)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_find_no_divergence_between_engines_on_seeded_soups() {
+        for (rule_name, np_universe, sp_universe) in seeded_soups(16) {
+            let divergence = DifferentialRun::new()
+                .with_engine("np", Box::new(np_universe))
+                .with_engine("sp", Box::new(sp_universe))
+                .run(20);
+
+            assert_eq!(divergence, None, "{}", rule_name);
+        }
+    }
+
+    #[test]
+    fn should_find_no_divergence_when_skipping_cells_whose_neighbourhood_did_not_change() {
+        for (rule_name, np_universe, sp_universe) in seeded_soups(40) {
+            // Cells that may not be updated are all computed, even when all of them are
+            let np_in_full = np_universe.clone().with_update_mode(UpdateMode::Probabilistic(1.0));
+
+            let divergence = DifferentialRun::new()
+                .with_engine("np", Box::new(np_universe))
+                .with_engine("np in full", Box::new(np_in_full))
+                .with_engine("sp", Box::new(sp_universe))
+                .run(60);
+
+            assert_eq!(divergence, None, "{}", rule_name);
+        }
+    }
+
+    #[test]
+    fn should_report_the_first_cell_that_differs() {
+        let states = vec![
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::ALIVE, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD, CellState::DEAD],
+        ];
+        let life = np::Universe::new_from_cell_states(states.clone());
+        let seeds = np::Universe::new_from_cell_states(states).with_rule(Rc::new(LifeLikeRule::parse("B2/S").unwrap()));
+
+        let divergence = DifferentialRun::new()
+            .with_engine("life", Box::new(life))
+            .with_engine("seeds", Box::new(seeds))
+            .run(5)
+            .unwrap();
+
+        assert_eq!((divergence.generation, divergence.x, divergence.y), (1, 1, 0));
+        assert_eq!(divergence.to_string(), "Engines diverge at generation 1, cell (1, 0): life o, seeds x");
+    }
+}
@@ -0,0 +1,246 @@
+use std::io;
+
+use crate::common::cell_state::CellState;
+use crate::common::simulation::Simulation;
+
+/// Share of generations in which a cell must change for its region to be
+/// reported as chaotic.
+static CHAOTIC_CHANGE_RATE: f64 = 0.5;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Region {
+    Empty,
+    Still,
+    Active,
+    Chaotic,
+}
+
+impl Region {
+    pub fn print(&self) -> String {
+        match self {
+            Region::Empty => String::from("."),
+            Region::Still => String::from("#"),
+            Region::Active => String::from("+"),
+            Region::Chaotic => String::from("*"),
+        }
+    }
+}
+
+/// Per-cell activity of a universe accumulated over a run: how many
+/// generations each cell was alive and how many times it changed state.
+#[derive(Debug)]
+pub struct HeatMap {
+    width: usize,
+    height: usize,
+    generations: usize,
+    alive_generations: Vec<Vec<usize>>,
+    changes: Vec<Vec<usize>>,
+    previous: Option<Vec<Vec<CellState>>>,
+}
+
+impl HeatMap {
+    pub fn new(width: usize, height: usize) -> HeatMap {
+        HeatMap {
+            width,
+            height,
+            generations: 0,
+            alive_generations: vec![vec![0; width]; height],
+            changes: vec![vec![0; width]; height],
+            previous: None,
+        }
+    }
+
+    /// Records the initial generation of the simulation and the `ticks` following ones.
+    pub fn record_run(simulation: &mut dyn Simulation, ticks: usize) -> HeatMap {
+        let states = simulation.states();
+        let mut heat_map = HeatMap::new(states.first().map_or(0, |line| line.len()), states.len());
+
+        heat_map.record(&states);
+        for _ in 0..ticks {
+            simulation.advance();
+            heat_map.record(&simulation.states());
+        }
+
+        heat_map
+    }
+
+    pub fn record(&mut self, states: &[Vec<CellState>]) {
+        for (y, line) in states.iter().enumerate().take(self.height) {
+            for (x, state) in line.iter().enumerate().take(self.width) {
+                if state.is_alive() {
+                    self.alive_generations[y][x] += 1;
+                }
+                if let Some(previous) = &self.previous {
+                    if &previous[y][x] != state {
+                        self.changes[y][x] += 1;
+                    }
+                }
+            }
+        }
+
+        self.generations += 1;
+        self.previous = Some(states.to_vec());
+    }
+
+    pub fn generations(&self) -> usize {
+        self.generations
+    }
+
+    pub fn alive_generations_of(&self, x: usize, y: usize) -> usize {
+        self.alive_generations[y][x]
+    }
+
+    pub fn changes_of(&self, x: usize, y: usize) -> usize {
+        self.changes[y][x]
+    }
+
+    pub fn region_of(&self, x: usize, y: usize) -> Region {
+        let changes = self.changes[y][x];
+        let transitions = self.generations.saturating_sub(1);
+
+        match changes {
+            0 if self.alive_generations[y][x] == 0 => Region::Empty,
+            0 => Region::Still,
+            _ if changes as f64 / transitions as f64 > CHAOTIC_CHANGE_RATE => Region::Chaotic,
+            _ => Region::Active,
+        }
+    }
+
+    /// Lines of region symbols coloured from cold (rarely changing) to hot
+    /// (changing at every generation), still lifes being drawn in green.
+    pub fn print(&self) -> Vec<String> {
+        (0..self.height)
+            .map(|y| (0..self.width)
+                .map(|x| match self.region_of(x, y) {
+                    Region::Empty => Region::Empty.print(),
+                    region => {
+                        let (red, green, blue) = self.colour_of(x, y);
+                        format!("\x1b[38;2;{};{};{}m{}\x1b[0m", red, green, blue, region.print())
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+            )
+            .collect()
+    }
+
+    /// Binary greyscale image where the brightness of a pixel is the number of changes of the cell.
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                image.push((self.change_ratio_of(x, y) * 255.0).round() as u8);
+            }
+        }
+        image
+    }
+
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut image: Vec<u8> = vec![];
+        {
+            let mut encoder = png::Encoder::new(&mut image, self.width as u32, self.height as u32);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+
+            let pixels = (0..self.height)
+                .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+                .flat_map(|(x, y)| {
+                    let (red, green, blue) = self.colour_of(x, y);
+                    [red, green, blue]
+                })
+                .collect::<Vec<u8>>();
+            writer.write_image_data(&pixels)?;
+        }
+        Ok(image)
+    }
+
+    fn change_ratio_of(&self, x: usize, y: usize) -> f64 {
+        match self.generations {
+            0 | 1 => 0.0,
+            n => self.changes[y][x] as f64 / (n - 1) as f64
+        }
+    }
+
+    fn colour_of(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        if self.region_of(x, y) == Region::Still {
+            return (0, 160, 0);
+        }
+
+        // Black, then blue, then red, then yellow as the cell gets busier
+        let ratio = self.change_ratio_of(x, y);
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match ratio {
+            r if r < 1.0 / 3.0 => (0, 0, channel(r * 3.0)),
+            r if r < 2.0 / 3.0 => (channel((r - 1.0 / 3.0) * 3.0), 0, channel(1.0 - (r - 1.0 / 3.0) * 3.0)),
+            r => (255, channel((r - 2.0 / 3.0) * 3.0), 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod heat_map_tests {
+    use super::*;
+    use crate::nopointer::universe::Universe;
+
+    fn blinker_next_to_block() -> Universe {
+        Universe::new_from_states(&vec![
+            "o o o o o o o",
+            "o x o o o o o",
+            "o x o o o x x",
+            "o x o o o x x",
+            "o o o o o o o",
+        ])
+    }
+
+    #[test]
+    fn should_count_alive_generations_and_changes_of_every_cell() {
+        let mut universe = blinker_next_to_block();
+
+        let heat_map = HeatMap::record_run(&mut universe, 4);
+
+        assert_eq!(heat_map.generations(), 5);
+        // Centre of the blinker never changes, its ends blink every generation
+        assert_eq!(heat_map.alive_generations_of(1, 2), 5);
+        assert_eq!(heat_map.changes_of(1, 2), 0);
+        assert_eq!(heat_map.alive_generations_of(1, 1), 3);
+        assert_eq!(heat_map.changes_of(1, 1), 4);
+        assert_eq!(heat_map.changes_of(0, 2), 4);
+    }
+
+    #[test]
+    fn should_highlight_stable_and_chaotic_regions() {
+        let mut universe = blinker_next_to_block();
+
+        let heat_map = HeatMap::record_run(&mut universe, 4);
+
+        assert_eq!(heat_map.region_of(3, 0), Region::Empty);
+        assert_eq!(heat_map.region_of(5, 2), Region::Still);
+        assert_eq!(heat_map.region_of(1, 2), Region::Still);
+        assert_eq!(heat_map.region_of(1, 1), Region::Chaotic);
+    }
+
+    #[test]
+    fn should_render_activity_as_pgm() {
+        let mut universe = blinker_next_to_block();
+
+        let heat_map = HeatMap::record_run(&mut universe, 4);
+        let image = heat_map.to_pgm();
+
+        let header = b"P5\n7 5\n255\n";
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(image.len(), header.len() + 7 * 5);
+        assert_eq!(image[header.len() + 7 + 1], 255);
+        assert_eq!(image[header.len() + 7 * 2 + 1], 0);
+    }
+
+    #[test]
+    fn should_render_activity_as_png() {
+        let mut universe = blinker_next_to_block();
+
+        let heat_map = HeatMap::record_run(&mut universe, 2);
+        let image = heat_map.to_png().unwrap();
+
+        assert_eq!(&image[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
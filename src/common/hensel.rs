@@ -0,0 +1,403 @@
+use std::collections::HashSet;
+
+use crate::common::cell_state::CellState;
+use crate::common::relative_position::RelativePosition;
+use crate::common::rule::Rule;
+
+// Neighbours are numbered clockwise from the north one, so that a quarter
+// turn adds 2 to every index and a reflection negates it
+static CLOCKWISE: [RelativePosition; 8] = [
+    RelativePosition::North,
+    RelativePosition::NorthEast,
+    RelativePosition::East,
+    RelativePosition::SouthEast,
+    RelativePosition::South,
+    RelativePosition::SouthWest,
+    RelativePosition::West,
+    RelativePosition::NorthWest,
+];
+
+// One configuration of each Hensel letter, the other ones being its
+// rotations and reflections. 5 to 7 neighbours are the complements of 3 to 1.
+static LETTERS: [(usize, char, &[usize]); 31] = [
+    (1, 'c', &[1]),
+    (1, 'e', &[0]),
+    (2, 'c', &[1, 3]),
+    (2, 'e', &[0, 2]),
+    (2, 'k', &[0, 3]),
+    (2, 'a', &[0, 1]),
+    (2, 'i', &[0, 4]),
+    (2, 'n', &[1, 5]),
+    (3, 'c', &[1, 3, 5]),
+    (3, 'e', &[0, 2, 4]),
+    (3, 'k', &[0, 2, 5]),
+    (3, 'a', &[0, 1, 2]),
+    (3, 'i', &[7, 0, 1]),
+    (3, 'n', &[0, 1, 3]),
+    (3, 'y', &[0, 3, 5]),
+    (3, 'q', &[0, 1, 5]),
+    (3, 'j', &[0, 1, 6]),
+    (3, 'r', &[0, 1, 4]),
+    (4, 'c', &[1, 3, 5, 7]),
+    (4, 'e', &[0, 2, 4, 6]),
+    (4, 'k', &[0, 1, 3, 6]),
+    (4, 'a', &[0, 1, 2, 3]),
+    (4, 'i', &[0, 1, 3, 4]),
+    (4, 'n', &[0, 1, 3, 7]),
+    (4, 'y', &[0, 1, 3, 5]),
+    (4, 'q', &[0, 1, 2, 5]),
+    (4, 'j', &[0, 1, 4, 6]),
+    (4, 'r', &[0, 1, 2, 4]),
+    (4, 't', &[7, 0, 1, 4]),
+    (4, 'w', &[0, 1, 5, 6]),
+    (4, 'z', &[0, 1, 4, 5]),
+];
+
+/// Isotropic non-totalistic rule written in Hensel notation, such as
+/// `B2-a/S12`: after each neighbour count, letters restrict the condition to
+/// some configurations of the neighbours, or exclude them when after a `-`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HenselRule {
+    rulestring: String,
+    birth: HashSet<u8>,
+    survival: HashSet<u8>,
+}
+
+impl HenselRule {
+    pub fn parse(rulestring: &str) -> Result<HenselRule, String> {
+        let mut birth: Option<HashSet<u8>> = None;
+        let mut survival: Option<HashSet<u8>> = None;
+
+        for part in rulestring.trim().split('/') {
+            let mut chars = part.chars();
+            match chars.next().map(|prefix| prefix.to_ascii_uppercase()) {
+                Some('B') if birth.is_none() => birth = Some(Self::parse_conditions(chars.as_str(), rulestring)?),
+                Some('S') if survival.is_none() => survival = Some(Self::parse_conditions(chars.as_str(), rulestring)?),
+                Some('C') | Some('G') => return Err(format!("Generations states are not supported in Hensel rule '{}'", rulestring)),
+                _ => return Err(format!("Invalid rule '{}'", rulestring))
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(HenselRule {
+                rulestring: rulestring.trim().to_string(),
+                birth,
+                survival,
+            }),
+            _ => Err(format!("Rule '{}' needs both birth and survival conditions", rulestring))
+        }
+    }
+
+    /// Every arrangement of `count` alive neighbours described by the letter, as bit masks over `CLOCKWISE`.
+    pub fn configurations_of(count: usize, letter: char) -> Option<HashSet<u8>> {
+        let complement = count > 4;
+        let base_count = if complement { 8 - count } else { count };
+
+        LETTERS
+            .iter()
+            .find(|(letter_count, letter_name, _)| *letter_count == base_count && *letter_name == letter)
+            .map(|(_, _, neighbours)| {
+                let mask = neighbours.iter().fold(0u8, |mask, index| mask | 1 << index);
+                Self::symmetries_of(if complement { !mask } else { mask })
+            })
+    }
+
+    fn symmetries_of(mask: u8) -> HashSet<u8> {
+        (0..4)
+            .flat_map(|quarter_turns| [false, true].map(|reflected| (quarter_turns, reflected)))
+            .map(|(quarter_turns, reflected)| {
+                (0..8)
+                    .filter(|index| mask & (1 << index) != 0)
+                    .fold(0u8, |transformed, index: usize| {
+                        let index = if reflected { 8 - index } else { index };
+                        transformed | 1 << ((index + 2 * quarter_turns) % 8)
+                    })
+            })
+            .collect()
+    }
+
+    fn parse_conditions(conditions: &str, rulestring: &str) -> Result<HashSet<u8>, String> {
+        let mut masks: HashSet<u8> = HashSet::new();
+        let mut chars = conditions.chars().peekable();
+
+        while let Some(count) = chars.next() {
+            let count = match count.to_digit(10) {
+                Some(count) if count <= 8 => count as usize,
+                _ => return Err(format!("Invalid neighbour count '{}' in rule '{}'", count, rulestring))
+            };
+            let excluded = chars.next_if_eq(&'-').is_some();
+
+            let mut letters: HashSet<u8> = HashSet::new();
+            while let Some(letter) = chars.next_if(|c| c.is_ascii_lowercase()) {
+                match Self::configurations_of(count, letter) {
+                    Some(configurations) => letters.extend(configurations),
+                    None => return Err(format!("Invalid configuration '{}{}' in rule '{}'", count, letter, rulestring))
+                }
+            }
+            if excluded && letters.is_empty() {
+                return Err(format!("Missing configurations after '{}-' in rule '{}'", count, rulestring));
+            }
+
+            let all = (0..=u8::MAX).filter(|mask| mask.count_ones() as usize == count);
+            match (letters.is_empty(), excluded) {
+                (true, _) => masks.extend(all),
+                (false, false) => masks.extend(letters),
+                (false, true) => masks.extend(all.filter(|mask| !letters.contains(mask))),
+            }
+        }
+
+        Ok(masks)
+    }
+
+    fn mask_of(neighbours: &[(CellState, RelativePosition)]) -> u8 {
+        neighbours
+            .iter()
+            .filter(|(state, _)| state.is_alive())
+            .filter_map(|(_, position)| CLOCKWISE.iter().position(|clockwise| clockwise == position))
+            .fold(0u8, |mask, index| mask | 1 << index)
+    }
+}
+
+impl Rule for HenselRule {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        let mask = Self::mask_of(neighbours);
+        match state {
+            CellState::ALIVE if self.survival.contains(&mask) => CellState::ALIVE,
+            CellState::DEAD if self.birth.contains(&mask) => CellState::ALIVE,
+            _ => CellState::DEAD
+        }
+    }
+
+    fn name(&self) -> String {
+        self.rulestring.clone()
+    }
+}
+
+#[cfg(test)]
+mod hensel_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::common::rule::LifeLikeRule;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    fn neighbours_at(alive: &[RelativePosition]) -> Vec<(CellState, RelativePosition)> {
+        CLOCKWISE
+            .iter()
+            .map(|position| (if alive.contains(position) { CellState::ALIVE } else { CellState::DEAD }, *position))
+            .collect()
+    }
+
+    fn neighbours_of_mask(mask: u8) -> Vec<(CellState, RelativePosition)> {
+        let alive = (0..8).filter(|index| mask & (1 << index) != 0).map(|index| CLOCKWISE[index]).collect::<Vec<_>>();
+        neighbours_at(&alive)
+    }
+
+    #[test]
+    fn should_split_every_neighbourhood_into_exactly_one_letter() {
+        let letters = ["", "ce", "cekain", "cekainyqjr", "cekainyqjrtwz", "cekainyqjr", "cekain", "ce", ""];
+        let mut seen: HashSet<u8> = HashSet::new();
+
+        for (count, count_letters) in letters.iter().enumerate() {
+            for letter in count_letters.chars() {
+                let configurations = HenselRule::configurations_of(count, letter).unwrap();
+                assert!(configurations.iter().all(|mask| mask.count_ones() as usize == count));
+                assert!(configurations.is_disjoint(&seen), "{}{} overlaps another letter", count, letter);
+                seen.extend(configurations);
+            }
+        }
+
+        assert_eq!(seen.len(), 256 - 2);
+    }
+
+    #[test]
+    fn should_match_golly_letters() {
+        use RelativePosition::*;
+        // One configuration of every letter as drawn in Golly's documentation
+        let golly: [(usize, char, &[RelativePosition]); 31] = [
+            (1, 'c', &[NorthEast]),
+            (1, 'e', &[North]),
+            (2, 'a', &[North, NorthEast]),
+            (2, 'c', &[NorthEast, SouthEast]),
+            (2, 'e', &[North, East]),
+            (2, 'i', &[North, South]),
+            (2, 'k', &[North, SouthEast]),
+            (2, 'n', &[NorthEast, SouthWest]),
+            (3, 'a', &[North, NorthEast, East]),
+            (3, 'c', &[NorthEast, SouthEast, SouthWest]),
+            (3, 'e', &[North, East, South]),
+            (3, 'i', &[NorthWest, North, NorthEast]),
+            (3, 'j', &[North, NorthEast, West]),
+            (3, 'k', &[North, East, SouthWest]),
+            (3, 'n', &[North, NorthEast, SouthEast]),
+            (3, 'q', &[North, NorthEast, SouthWest]),
+            (3, 'r', &[North, NorthEast, South]),
+            (3, 'y', &[North, SouthEast, SouthWest]),
+            (4, 'a', &[North, NorthEast, East, SouthEast]),
+            (4, 'c', &[NorthEast, SouthEast, SouthWest, NorthWest]),
+            (4, 'e', &[North, East, South, West]),
+            (4, 'i', &[North, NorthEast, SouthEast, South]),
+            (4, 'j', &[North, NorthEast, South, West]),
+            (4, 'k', &[North, NorthEast, SouthEast, West]),
+            (4, 'n', &[North, NorthEast, SouthEast, NorthWest]),
+            (4, 'q', &[North, NorthEast, East, SouthWest]),
+            (4, 'r', &[North, NorthEast, East, South]),
+            (4, 't', &[NorthWest, North, NorthEast, South]),
+            (4, 'w', &[North, NorthEast, SouthWest, West]),
+            (4, 'y', &[North, NorthEast, SouthEast, SouthWest]),
+            (4, 'z', &[North, NorthEast, South, SouthWest]),
+        ];
+
+        for (count, letter, alive) in golly {
+            let rule = HenselRule::parse(&format!("B{}{}/S", count, letter)).unwrap();
+            assert_eq!(rule.next_state(&CellState::DEAD, &neighbours_at(alive)), CellState::ALIVE, "{}{}", count, letter);
+
+            // 5 to 7 neighbours are named after the dead ones
+            if count < 4 {
+                let complement = CLOCKWISE.iter().filter(|position| !alive.contains(position)).copied().collect::<Vec<_>>();
+                let rule = HenselRule::parse(&format!("B/S{}{}", 8 - count, letter)).unwrap();
+                assert_eq!(rule.next_state(&CellState::ALIVE, &neighbours_at(&complement)), CellState::ALIVE, "{}{}", 8 - count, letter);
+            }
+        }
+    }
+
+    #[test]
+    fn should_behave_as_conway_without_letters() {
+        let hensel = HenselRule::parse("B3/S23").unwrap();
+
+        for mask in 0..=u8::MAX {
+            let neighbours = neighbours_of_mask(mask);
+            for state in [CellState::ALIVE, CellState::DEAD] {
+                assert_eq!(hensel.next_state(&state, &neighbours), LifeLikeRule::conway().next_state(&state, &neighbours));
+            }
+        }
+    }
+
+    #[test]
+    fn should_exclude_configurations_after_a_minus() {
+        let rule = HenselRule::parse("B2-a/S12").unwrap();
+
+        let adjacent = neighbours_at(&[RelativePosition::North, RelativePosition::NorthEast]);
+        let opposite = neighbours_at(&[RelativePosition::West, RelativePosition::East]);
+
+        assert_eq!(rule.next_state(&CellState::DEAD, &adjacent), CellState::DEAD);
+        assert_eq!(rule.next_state(&CellState::DEAD, &opposite), CellState::ALIVE);
+        assert_eq!(rule.next_state(&CellState::ALIVE, &adjacent), CellState::ALIVE);
+    }
+
+    #[test]
+    fn should_only_keep_listed_configurations() {
+        // tlife
+        let rule = HenselRule::parse("B3/S2-i34q").unwrap();
+
+        let line = neighbours_at(&[RelativePosition::North, RelativePosition::South]);
+        let corner = neighbours_at(&[RelativePosition::North, RelativePosition::East]);
+        let four_q = neighbours_at(&[RelativePosition::North, RelativePosition::NorthEast, RelativePosition::East, RelativePosition::SouthWest]);
+        let four_e = neighbours_at(&[RelativePosition::North, RelativePosition::East, RelativePosition::South, RelativePosition::West]);
+
+        assert_eq!(rule.next_state(&CellState::ALIVE, &line), CellState::DEAD);
+        assert_eq!(rule.next_state(&CellState::ALIVE, &corner), CellState::ALIVE);
+        assert_eq!(rule.next_state(&CellState::ALIVE, &four_q), CellState::ALIVE);
+        assert_eq!(rule.next_state(&CellState::ALIVE, &four_e), CellState::DEAD);
+    }
+
+    #[test]
+    fn should_complement_three_to_one_neighbour_letters() {
+        let five_i = HenselRule::configurations_of(5, 'i').unwrap();
+        let three_i = HenselRule::configurations_of(3, 'i').unwrap();
+
+        assert!(five_i.iter().all(|mask| three_i.contains(&!mask)));
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        assert!(HenselRule::parse("B2x/S23").is_err());
+        assert!(HenselRule::parse("B1k/S23").is_err());
+        assert!(HenselRule::parse("B2-/S23").is_err());
+        assert!(HenselRule::parse("B3").is_err());
+        assert_eq!(HenselRule::parse("B2-a/S12/C3"), Err(String::from("Generations states are not supported in Hensel rule 'B2-a/S12/C3'")));
+    }
+
+    fn after_one_tick_on_both_engines(rulestring: &str, state: &[&str]) -> Vec<String> {
+        let rule = Rc::new(HenselRule::parse(rulestring).unwrap());
+        let np_universe = np::Universe::new_from_states(&state.to_vec()).with_rule(rule.clone()).tick();
+        let sp_universe = sp::Universe::new_with_defined_states(np::Universe::new_from_states(&state.to_vec()).states()).with_rule(rule);
+        sp_universe.tick();
+
+        assert_eq!(sp_universe.print(), np_universe.print());
+        np_universe.print()
+    }
+
+    #[test]
+    fn should_kill_the_blinker_in_tlife() {
+        // The middle cell of the blinker only has its two opposite (2i) neighbours
+        let state = [
+            "o o o o o",
+            "o o o o o",
+            "o x x x o",
+            "o o o o o",
+            "o o o o o",
+        ];
+
+        let next = after_one_tick_on_both_engines("B3/S2-i34q", &state);
+
+        assert_eq!(next, vec!["o o o o o", "o o x o o", "o o o o o", "o o x o o", "o o o o o"]);
+    }
+
+    #[test]
+    fn should_keep_the_domino_still_in_just_friends() {
+        // Cells next to the domino see it as two adjacent (2a) neighbours
+        let state = [
+            "o o o o o o",
+            "o o o o o o",
+            "o o x x o o",
+            "o o o o o o",
+            "o o o o o o",
+        ];
+
+        let next = after_one_tick_on_both_engines("B2-a/S12", &state);
+
+        assert_eq!(next, vec!["o o o o o o", "o o o o o o", "o o x x o o", "o o o o o o", "o o o o o o"]);
+    }
+
+    #[test]
+    fn should_fill_the_diagonal_pair_into_a_block_in_just_friends() {
+        // The two other cells of the block see the pair as 2e neighbours
+        let state = [
+            "o o o o o o",
+            "o o o o o o",
+            "o o x o o o",
+            "o o o x o o",
+            "o o o o o o",
+            "o o o o o o",
+        ];
+
+        let next = after_one_tick_on_both_engines("B2-a/S12", &state);
+
+        assert_eq!(next, vec!["o o o o o o", "o o o o o o", "o o x x o o", "o o x x o o", "o o o o o o", "o o o o o o"]);
+    }
+
+    #[test]
+    fn should_give_neighbours_positions_the_same_way_in_both_engines() {
+        let state = vec![
+            "o o o o o o",
+            "o x o o x o",
+            "o x o x o o",
+            "o o o x x o",
+            "o o o o o o",
+        ];
+        let rule = Rc::new(HenselRule::parse("B2-a3/S12").unwrap());
+        let mut np_universe = np::Universe::new_from_states(&state).with_rule(rule.clone());
+        let sp_universe = sp::Universe::new_with_defined_states(np_universe.states()).with_rule(rule);
+
+        let first_tick = np_universe.tick();
+        assert_ne!(first_tick.print(), np::Universe::new_from_states(&state).with_rule(Rc::new(LifeLikeRule::parse("B23/S12").unwrap())).tick().print());
+
+        for _ in 0..5 {
+            np_universe = np_universe.tick();
+            sp_universe.tick();
+            assert_eq!(sp_universe.print(), np_universe.print());
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::cell_state::CellState;
+
+/// What a tick changed: the cells it updated with the states they had
+/// before, and the counters of the generation it started from.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct GenerationDelta {
+    pub generation: usize,
+    pub births: usize,
+    pub deaths: usize,
+    changes: Vec<(usize, usize, CellState)>,
+}
+
+impl GenerationDelta {
+    pub fn between(generation: usize, births: usize, deaths: usize, before: &[Vec<CellState>], after: &[Vec<CellState>]) -> GenerationDelta {
+        let changes = before
+            .iter()
+            .zip(after)
+            .enumerate()
+            .flat_map(|(y, (line_before, line_after))| line_before
+                .iter()
+                .zip(line_after)
+                .enumerate()
+                .filter(|(_, (state_before, state_after))| state_before != state_after)
+                .map(move |(x, (state_before, _))| (x, y, state_before.clone()))
+            )
+            .collect();
+        GenerationDelta { generation, births, deaths, changes }
+    }
+
+    pub fn changed_cells(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Follows the cells of a universe moved by the offset into a grid of the
+    /// given size, forgetting the ones moved outside of it.
+    pub fn translate(&mut self, (offset_x, offset_y): (i64, i64), (width, height): (usize, usize)) {
+        self.changes = self
+            .changes
+            .drain(..)
+            .filter_map(|(x, y, state)| {
+                let x = usize::try_from(x as i64 + offset_x).ok().filter(|x| *x < width)?;
+                let y = usize::try_from(y as i64 + offset_y).ok().filter(|y| *y < height)?;
+                Some((x, y, state))
+            })
+            .collect();
+    }
+
+    fn fits(&self, width: usize, height: usize) -> bool {
+        self.changes.iter().all(|(x, y, _)| *x < width && *y < height)
+    }
+
+    /// Puts the changed cells back in the states they had before the tick.
+    pub fn restore(&self, states: &mut [Vec<CellState>]) {
+        for (x, y, state) in &self.changes {
+            states[*y][*x] = state.clone();
+        }
+    }
+}
+
+/// Bounded record of the last ticks, each one kept as a delta so that long
+/// runs on large universes only cost the cells that actually changed. The
+/// oldest deltas are dropped once the limit is reached, and a limit of zero
+/// records nothing.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    limit: usize,
+    deltas: VecDeque<GenerationDelta>,
+}
+
+impl History {
+    pub fn new(limit: usize) -> History {
+        History {
+            limit,
+            deltas: VecDeque::with_capacity(limit),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.limit > 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.deltas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+
+    /// Oldest generation that can still be stepped back to.
+    pub fn earliest_generation(&self) -> Option<usize> {
+        self.deltas.front().map(|delta| delta.generation)
+    }
+
+    pub fn record(&mut self, delta: GenerationDelta) {
+        if !self.is_recording() {
+            return;
+        }
+        if self.deltas.len() == self.limit {
+            self.deltas.pop_front();
+        }
+        self.deltas.push_back(delta);
+    }
+
+    /// Keeps the deltas in line with a universe that was resized.
+    pub fn translate(&mut self, offset: (i64, i64), size: (usize, usize)) {
+        self.deltas.iter_mut().for_each(|delta| delta.translate(offset, size));
+    }
+
+    /// Whether the deltas could all have been recorded by a universe of the
+    /// given size, as a history read back from a file has to be.
+    pub fn fits(&self, width: usize, height: usize) -> bool {
+        self.deltas.len() <= self.limit && self.deltas.iter().all(|delta| delta.fits(width, height))
+    }
+
+    /// Delta of the last tick, forgotten from now on.
+    pub fn undo(&mut self) -> Option<GenerationDelta> {
+        self.deltas.pop_back()
+    }
+}
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    fn states(lines: &[&str]) -> Vec<Vec<CellState>> {
+        lines.iter().map(|line| line.chars().map(CellState::from_char).collect()).collect()
+    }
+
+    #[test]
+    fn should_only_keep_changed_cells() {
+        let before = states(&["xoo", "ooo", "oox"]);
+        let after = states(&["xxo", "ooo", "ooo"]);
+
+        let delta = GenerationDelta::between(3, 1, 0, &before, &after);
+        let mut restored = after.clone();
+        delta.restore(&mut restored);
+
+        assert_eq!(delta.changed_cells(), 2);
+        assert_eq!(restored, before);
+    }
+
+    #[test]
+    fn should_follow_cells_moved_by_a_resize() {
+        let before = states(&["xo", "ox"]);
+        let after = states(&["oo", "oo"]);
+        let mut history = History::new(1);
+        history.record(GenerationDelta::between(0, 0, 2, &before, &after));
+
+        history.translate((1, -1), (3, 1));
+        let mut restored = states(&["ooo"]);
+        history.undo().unwrap().restore(&mut restored);
+
+        assert_eq!(restored, states(&["oox"]));
+    }
+
+    #[test]
+    fn should_drop_the_oldest_deltas_beyond_the_limit() {
+        let mut history = History::new(2);
+        for generation in 0..5 {
+            history.record(GenerationDelta::between(generation, 0, 0, &states(&["x"]), &states(&["o"])));
+        }
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.earliest_generation(), Some(3));
+        assert_eq!(history.undo().map(|delta| delta.generation), Some(4));
+        assert_eq!(history.undo().map(|delta| delta.generation), Some(3));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn should_record_nothing_without_a_limit() {
+        let mut history = History::default();
+
+        history.record(GenerationDelta::between(0, 0, 0, &states(&["x"]), &states(&["o"])));
+
+        assert!(history.is_empty());
+    }
+}
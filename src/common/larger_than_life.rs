@@ -0,0 +1,221 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use crate::common::cell_state::{CellState, MAX_DYING_STAGE};
+use crate::common::neighbourhood::Neighbourhood;
+use crate::common::relative_position::RelativePosition;
+use crate::common::rule::{Rule, TotalisticRule};
+
+/// Totalistic rule over a large neighbourhood, written in Golly's notation
+/// such as `R5,C0,M1,S34..58,B34..45,NM` (Bosco's rule): range, number of
+/// states, whether the cell counts itself, survival and birth intervals, and
+/// Moore (`NM`) or von Neumann (`NN`) neighbourhood.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LargerThanLifeRule {
+    range: usize,
+    states: u8,
+    middle: bool,
+    survival: RangeInclusive<usize>,
+    birth: RangeInclusive<usize>,
+    von_neumann: bool,
+}
+
+impl LargerThanLifeRule {
+    pub fn bosco() -> LargerThanLifeRule {
+        LargerThanLifeRule {
+            range: 5,
+            states: 2,
+            middle: true,
+            survival: 34..=58,
+            birth: 34..=45,
+            von_neumann: false,
+        }
+    }
+
+    pub fn parse(rulestring: &str) -> Result<LargerThanLifeRule, String> {
+        let invalid = || format!("Invalid Larger than Life rule '{}'", rulestring);
+        let mut range: Option<usize> = None;
+        let mut states: u8 = 2;
+        let mut middle = false;
+        let mut survival: Option<RangeInclusive<usize>> = None;
+        let mut birth: Option<RangeInclusive<usize>> = None;
+        let mut von_neumann = false;
+
+        for part in rulestring.trim().split(',') {
+            let part = part.trim();
+            if part.is_empty() || !part.is_char_boundary(1) {
+                return Err(invalid());
+            }
+            let (prefix, value) = part.split_at(1);
+            match prefix.to_ascii_uppercase().as_str() {
+                "R" if range.is_none() => range = Some(value.parse::<usize>().ok().filter(|range| *range > 0).ok_or_else(invalid)?),
+                "C" => states = value.parse::<u8>().ok().filter(|states| *states <= MAX_DYING_STAGE + 2).ok_or_else(invalid)?.max(2),
+                "M" => middle = match value {
+                    "0" => false,
+                    "1" => true,
+                    _ => return Err(invalid())
+                },
+                "S" if survival.is_none() => survival = Some(Self::parse_interval(value).ok_or_else(invalid)?),
+                "B" if birth.is_none() => birth = Some(Self::parse_interval(value).ok_or_else(invalid)?),
+                "N" => von_neumann = match value.to_ascii_uppercase().as_str() {
+                    "M" => false,
+                    "N" => true,
+                    _ => return Err(invalid())
+                },
+                _ => return Err(invalid())
+            }
+        }
+
+        match (range, survival, birth) {
+            (Some(range), Some(survival), Some(birth)) => Ok(LargerThanLifeRule { range, states, middle, survival, birth, von_neumann }),
+            _ => Err(format!("Rule '{}' needs a range, survival and birth conditions", rulestring))
+        }
+    }
+
+    pub fn range(&self) -> usize {
+        self.range
+    }
+
+    fn parse_interval(interval: &str) -> Option<RangeInclusive<usize>> {
+        match interval.split_once("..") {
+            Some((min, max)) => Some(min.parse().ok()?..=max.parse().ok()?),
+            None => interval.parse().ok().map(|count| count..=count),
+        }
+    }
+
+    fn decay(&self, stage: u8) -> CellState {
+        if stage + 2 < self.states {
+            CellState::DYING(stage + 1)
+        } else {
+            CellState::DEAD
+        }
+    }
+}
+
+impl fmt::Display for LargerThanLifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "R{},C{},M{},S{}..{},B{}..{},N{}",
+            self.range,
+            if self.states > 2 { self.states } else { 0 },
+            self.middle as u8,
+            self.survival.start(),
+            self.survival.end(),
+            self.birth.start(),
+            self.birth.end(),
+            if self.von_neumann { "N" } else { "M" }
+        )
+    }
+}
+
+impl TotalisticRule for LargerThanLifeRule {
+    fn next_state_from_count(&self, state: &CellState, alive_neighbours: usize) -> CellState {
+        let count = alive_neighbours + (self.middle && state.is_alive()) as usize;
+        match state {
+            CellState::ALIVE | CellState::COLOURED(_) if self.survival.contains(&count) => state.clone(),
+            CellState::ALIVE | CellState::COLOURED(_) => self.decay(0),
+            CellState::DYING(stage) => self.decay(*stage),
+            CellState::DEAD if self.birth.contains(&count) => CellState::ALIVE,
+            CellState::DEAD | CellState::STATE(_) => CellState::DEAD,
+        }
+    }
+}
+
+impl Rule for LargerThanLifeRule {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        let alive_neighbours = neighbours.iter().filter(|(state, _)| state.is_alive()).count();
+        self.next_state_from_count(state, alive_neighbours)
+    }
+
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    fn neighbourhood(&self) -> Option<Neighbourhood> {
+        Some(match self.von_neumann {
+            true => Neighbourhood::VonNeumann(self.range),
+            false => Neighbourhood::Moore(self.range),
+        })
+    }
+
+    fn as_totalistic(&self) -> Option<&dyn TotalisticRule> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod larger_than_life_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::common::rule::LifeLikeRule;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    #[test]
+    fn should_parse_and_print_bosco_rule() {
+        let bosco = LargerThanLifeRule::parse("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+
+        assert_eq!(bosco, LargerThanLifeRule::bosco());
+        assert_eq!(bosco.to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+        assert_eq!(bosco.neighbourhood(), Some(Neighbourhood::Moore(5)));
+    }
+
+    #[test]
+    fn should_parse_single_counts_and_von_neumann_neighbourhood() {
+        let rule = LargerThanLifeRule::parse("r2,c3,m0,s4,b3..5,nn").unwrap();
+
+        assert_eq!(rule.to_string(), "R2,C3,M0,S4..4,B3..5,NN");
+        assert_eq!(rule.neighbourhood(), Some(Neighbourhood::VonNeumann(2)));
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        assert!(LargerThanLifeRule::parse("R0,C0,M1,S34..58,B34..45,NM").is_err());
+        assert!(LargerThanLifeRule::parse("R5,C0,M2,S34..58,B34..45,NM").is_err());
+        assert!(LargerThanLifeRule::parse("R5,C38,M1,S34..58,B34..45,NM").is_err());
+        assert!(LargerThanLifeRule::parse("R5,C0,M1,S34..,B34..45,NM").is_err());
+        assert!(LargerThanLifeRule::parse("R5,C0,M1,B34..45,NM").is_err());
+        assert!(LargerThanLifeRule::parse("B3/S23").is_err());
+    }
+
+    #[test]
+    fn should_count_the_cell_itself_when_middle_is_included() {
+        let rule = LargerThanLifeRule::parse("R1,C0,M1,S3..4,B3,NM").unwrap();
+
+        assert_eq!(rule.next_state_from_count(&CellState::ALIVE, 2), CellState::ALIVE);
+        assert_eq!(rule.next_state_from_count(&CellState::ALIVE, 4), CellState::DEAD);
+        assert_eq!(rule.next_state_from_count(&CellState::DEAD, 3), CellState::ALIVE);
+    }
+
+    #[test]
+    fn should_behave_as_life_with_range_one() {
+        let life = LargerThanLifeRule::parse("R1,C0,M0,S2..3,B3,NM").unwrap();
+        let state = vec![
+            "o o o o o",
+            "o o x o o",
+            "o o x o o",
+            "o o x o o",
+            "o o o o o",
+        ];
+
+        let ltl_universe = np::Universe::new_from_states(&state).with_rule(Rc::new(life));
+        let life_universe = np::Universe::new_from_states(&state).with_rule(Rc::new(LifeLikeRule::conway()));
+
+        assert_eq!(ltl_universe.tick().print(), life_universe.tick().print());
+    }
+
+    #[test]
+    fn should_tick_alike_in_both_engines() {
+        let rule: Rc<dyn Rule> = Rc::new(LargerThanLifeRule::parse("R2,C0,M1,S6..12,B7..9,NM").unwrap());
+        let mut np_universe = np::Universe::new(12, 10).with_rule(Rc::clone(&rule));
+        let sp_universe = sp::Universe::new_with_defined_states(np_universe.states()).with_rule(rule);
+
+        for _ in 0..5 {
+            np_universe = np_universe.tick();
+            sp_universe.tick();
+            assert_eq!(np_universe.print(), sp_universe.print());
+        }
+    }
+}
@@ -1,2 +1,26 @@
+pub mod active_region;
+pub mod age_map;
+pub mod catalogue;
+pub mod cell_age;
 pub mod cell_state;
-pub mod relative_position;
\ No newline at end of file
+pub mod differential;
+pub mod heat_map;
+pub mod hensel;
+pub mod history;
+pub mod larger_than_life;
+pub mod multi_colour;
+pub mod neighbourhood;
+pub mod pattern;
+pub mod relative_position;
+pub mod resize;
+pub mod rule;
+pub mod simulation;
+pub mod snapshot;
+pub mod stamp;
+pub mod statistics;
+pub mod stochastic;
+pub mod summed_area_table;
+pub mod topology;
+pub mod transform;
+pub mod update_mode;
+pub mod wireworld;
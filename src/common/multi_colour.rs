@@ -0,0 +1,126 @@
+use crate::common::cell_state::CellState;
+use crate::common::relative_position::RelativePosition;
+use crate::common::rule::Rule;
+
+/// Conway's Life with alive cells of several colours: survivors keep their
+/// colour and a newborn cell takes the majority colour of its three parents,
+/// or with four colours the one none of them has when they all differ.
+#[derive(Debug, PartialEq, Clone)]
+pub struct MultiColourRule {
+    colours: u8,
+}
+
+impl MultiColourRule {
+    pub fn immigration() -> MultiColourRule {
+        MultiColourRule { colours: 2 }
+    }
+
+    pub fn quad_life() -> MultiColourRule {
+        MultiColourRule { colours: 4 }
+    }
+
+    fn colour_of_newborn(&self, parents: &[u8]) -> u8 {
+        let mut counts = vec![0; self.colours as usize + 1];
+        for colour in parents {
+            counts[*colour as usize] += 1;
+        }
+
+        match (1..=self.colours).max_by_key(|colour| counts[*colour as usize]) {
+            Some(colour) if counts[colour as usize] > 1 => colour,
+            _ => (1..=self.colours).find(|colour| counts[*colour as usize] == 0).unwrap_or(1),
+        }
+    }
+}
+
+impl Rule for MultiColourRule {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        let parents = neighbours.iter().filter_map(|(state, _)| state.colour()).collect::<Vec<u8>>();
+        match (state.is_alive(), parents.len()) {
+            (true, 2 | 3) => state.clone(),
+            (false, 3) => CellState::of_colour(self.colour_of_newborn(&parents)),
+            _ => CellState::DEAD,
+        }
+    }
+
+    fn name(&self) -> String {
+        match self.colours {
+            2 => String::from("Immigration"),
+            _ => String::from("QuadLife"),
+        }
+    }
+
+    fn colours(&self) -> u8 {
+        self.colours
+    }
+
+    fn state_of_index(&self, index: u8) -> CellState {
+        match index {
+            0 => CellState::DEAD,
+            colour => CellState::of_colour(colour.min(self.colours)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod multi_colour_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::common::pattern::Pattern;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    fn parents(colours: &[u8]) -> Vec<(CellState, RelativePosition)> {
+        colours.iter().map(|colour| (CellState::of_colour(*colour), RelativePosition::North)).collect()
+    }
+
+    #[test]
+    fn should_give_birth_to_the_majority_colour_of_the_parents() {
+        let immigration = MultiColourRule::immigration();
+
+        assert_eq!(immigration.next_state(&CellState::DEAD, &parents(&[2, 1, 2])), CellState::COLOURED(2));
+        assert_eq!(immigration.next_state(&CellState::DEAD, &parents(&[1, 1, 2])), CellState::ALIVE);
+    }
+
+    #[test]
+    fn should_give_birth_to_the_missing_colour_when_parents_all_differ() {
+        let quad_life = MultiColourRule::quad_life();
+
+        assert_eq!(quad_life.next_state(&CellState::DEAD, &parents(&[1, 4, 2])), CellState::COLOURED(3));
+        assert_eq!(quad_life.next_state(&CellState::DEAD, &parents(&[3, 4, 3])), CellState::COLOURED(3));
+    }
+
+    #[test]
+    fn should_keep_the_colour_of_survivors() {
+        let quad_life = MultiColourRule::quad_life();
+
+        assert_eq!(quad_life.next_state(&CellState::COLOURED(4), &parents(&[1, 2])), CellState::COLOURED(4));
+        assert_eq!(quad_life.next_state(&CellState::COLOURED(4), &parents(&[1])), CellState::DEAD);
+    }
+
+    #[test]
+    fn should_read_colours_of_golly_patterns() {
+        let pattern = Pattern::from_rle("x = 3, y = 1, rule = Immigration\nABA!").unwrap();
+        let rule = MultiColourRule::immigration();
+
+        let states = pattern.states()[0].iter().map(|state| rule.state_of_index(state.index())).collect::<Vec<CellState>>();
+
+        assert_eq!(states, vec![CellState::ALIVE, CellState::COLOURED(2), CellState::ALIVE]);
+    }
+
+    #[test]
+    fn should_tick_alike_in_both_engines() {
+        let state = vec![
+            "o o o o o",
+            "o b x b o",
+            "o o o o o",
+        ];
+        let np_universe = np::Universe::new_from_states(&state).with_rule(Rc::new(MultiColourRule::immigration()));
+        let sp_universe = sp::Universe::new_with_defined_states(np_universe.states()).with_rule(Rc::new(MultiColourRule::immigration()));
+
+        sp_universe.tick();
+
+        assert_eq!(np_universe.tick().print(), vec!["o o b o o", "o o x o o", "o o b o o"]);
+        assert_eq!(sp_universe.print(), vec!["o o b o o", "o o x o o", "o o b o o"]);
+    }
+}
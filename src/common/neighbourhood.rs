@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// Cells a cell looks at to compute its next state.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Neighbourhood {
+    /// Square of side `2r + 1` around the cell, 8 neighbours at range 1.
+    Moore(usize),
+    /// Cells at a Manhattan distance of at most `r`, 4 neighbours at range 1.
+    VonNeumann(usize),
+    /// Six neighbours of a hexagonal grid emulated on the square one, as Golly
+    /// does: the north-east and south-west cells are ignored.
+    Hexagonal,
+}
+
+impl Neighbourhood {
+    /// Offsets of the neighbours, from the north-west to the south-east one line after line.
+    pub fn offsets(&self) -> Vec<(i32, i32)> {
+        let range = match self {
+            Neighbourhood::Moore(range) | Neighbourhood::VonNeumann(range) => *range as i32,
+            Neighbourhood::Hexagonal => 1,
+        };
+
+        (-range..=range)
+            .flat_map(|y| (-range..=range).map(move |x| (x, y)))
+            .filter(|offset| offset != &(0, 0))
+            .filter(|(x, y)| match self {
+                Neighbourhood::Moore(_) => true,
+                Neighbourhood::VonNeumann(range) => (x.abs() + y.abs()) as usize <= *range,
+                Neighbourhood::Hexagonal => (*x, *y) != (1, -1) && (*x, *y) != (-1, 1),
+            })
+            .collect()
+    }
+
+    pub fn range(&self) -> usize {
+        match self {
+            Neighbourhood::Moore(range) | Neighbourhood::VonNeumann(range) => *range,
+            Neighbourhood::Hexagonal => 1,
+        }
+    }
+
+    /// Reads `moore`, `vonneumann` and `hexagonal`, optionally followed by a range such as `moore:2`.
+    pub fn parse(neighbourhood: &str) -> Result<Neighbourhood, String> {
+        let (name, range) = match neighbourhood.trim().split_once(':') {
+            Some((name, range)) => match range.parse::<usize>() {
+                Ok(range) if range > 0 => (name, range),
+                _ => return Err(format!("Invalid range '{}' in neighbourhood '{}'", range, neighbourhood))
+            },
+            None => (neighbourhood.trim(), 1),
+        };
+
+        match (name.to_ascii_lowercase().as_str(), range) {
+            ("moore", range) => Ok(Neighbourhood::Moore(range)),
+            ("vonneumann", range) => Ok(Neighbourhood::VonNeumann(range)),
+            ("hexagonal", 1) => Ok(Neighbourhood::Hexagonal),
+            _ => Err(format!("Invalid neighbourhood '{}'", neighbourhood))
+        }
+    }
+}
+
+impl Default for Neighbourhood {
+    fn default() -> Neighbourhood {
+        Neighbourhood::Moore(1)
+    }
+}
+
+impl fmt::Display for Neighbourhood {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Neighbourhood::Moore(1) => write!(f, "moore"),
+            Neighbourhood::Moore(range) => write!(f, "moore:{}", range),
+            Neighbourhood::VonNeumann(1) => write!(f, "vonneumann"),
+            Neighbourhood::VonNeumann(range) => write!(f, "vonneumann:{}", range),
+            Neighbourhood::Hexagonal => write!(f, "hexagonal"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod neighbourhood_tests {
+    use super::*;
+
+    #[test]
+    fn should_count_neighbours_of_every_neighbourhood() {
+        assert_eq!(Neighbourhood::Moore(1).offsets().len(), 8);
+        assert_eq!(Neighbourhood::Moore(2).offsets().len(), 24);
+        assert_eq!(Neighbourhood::VonNeumann(1).offsets().len(), 4);
+        assert_eq!(Neighbourhood::VonNeumann(2).offsets().len(), 12);
+        assert_eq!(Neighbourhood::Hexagonal.offsets().len(), 6);
+    }
+
+    #[test]
+    fn should_list_neighbours_line_after_line() {
+        assert_eq!(Neighbourhood::VonNeumann(1).offsets(), vec![(0, -1), (-1, 0), (1, 0), (0, 1)]);
+        assert_eq!(Neighbourhood::Hexagonal.offsets(), vec![(-1, -1), (0, -1), (-1, 0), (1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn should_parse_and_print_neighbourhoods() {
+        for name in ["moore", "moore:3", "vonneumann", "vonneumann:2", "hexagonal"] {
+            assert_eq!(Neighbourhood::parse(name).unwrap().to_string(), name);
+        }
+        assert!(Neighbourhood::parse("hexagonal:2").is_err());
+        assert!(Neighbourhood::parse("moore:0").is_err());
+        assert!(Neighbourhood::parse("margolus").is_err());
+    }
+}
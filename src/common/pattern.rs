@@ -0,0 +1,318 @@
+use crate::common::cell_state::CellState;
+use crate::common::transform::Transform;
+
+static RLE_LINE_LENGTH: usize = 70;
+
+/// Rectangular block of cell states, optionally with the rule it was made
+/// for, that can be read from and written to RLE files.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pattern {
+    states: Vec<Vec<CellState>>,
+    rule: Option<String>,
+}
+
+impl Pattern {
+    pub fn new(states: Vec<Vec<CellState>>) -> Pattern {
+        let width = states.iter().map(|line| line.len()).max().unwrap_or(0);
+        Pattern {
+            states: states
+                .into_iter()
+                .map(|mut line| {
+                    line.resize(width, CellState::DEAD);
+                    line
+                })
+                .collect(),
+            rule: None,
+        }
+    }
+
+    /// Reads lines of `x` and `o` as universes do in `new_from_states`.
+    pub fn from_text(lines: &[&str]) -> Pattern {
+        Pattern::new(lines
+            .iter()
+            .map(|line| line.chars().filter(|cell| cell != &' ').map(CellState::from_char).collect())
+            .collect())
+    }
+
+    pub fn from_rle_file(path: &str) -> Result<Pattern, String> {
+        let rle = std::fs::read_to_string(path).map_err(|error| format!("Could not read {}: {}", path, error))?;
+        Pattern::from_rle(&rle)
+    }
+
+    pub fn with_rule(self, rule: &str) -> Pattern {
+        Pattern {
+            rule: Some(rule.to_string()),
+            ..self
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.states.first().map_or(0, |line| line.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn states(&self) -> &Vec<Vec<CellState>> {
+        &self.states
+    }
+
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let (width, height) = transform.size(self.width(), self.height());
+        let mut states = vec![vec![CellState::DEAD; width]; height];
+        for (y, line) in self.states.iter().enumerate() {
+            for (x, state) in line.iter().enumerate() {
+                let (new_x, new_y) = transform.apply(x, y, self.width(), self.height());
+                states[new_y][new_x] = state.clone();
+            }
+        }
+        Pattern {
+            states,
+            rule: self.rule.clone(),
+        }
+    }
+
+    /// States of a `width` x `height` grid with the pattern in its middle,
+    /// the grid being enlarged when the pattern does not fit.
+    pub fn centered_in(&self, width: usize, height: usize) -> Vec<Vec<CellState>> {
+        let width = width.max(self.width());
+        let height = height.max(self.height());
+        let offset_x = (width - self.width()) / 2;
+        let offset_y = (height - self.height()) / 2;
+
+        let mut states = vec![vec![CellState::DEAD; width]; height];
+        for (y, line) in self.states.iter().enumerate() {
+            for (x, state) in line.iter().enumerate() {
+                states[y + offset_y][x + offset_x] = state.clone();
+            }
+        }
+        states
+    }
+
+    /// Reads two-state (`b`, `o`) and multi-state (`.`, `A`..`X`, `pA`..`yO`) RLE.
+    pub fn from_rle(rle: &str) -> Result<Pattern, String> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut rule: Option<String> = None;
+        let mut states: Vec<Vec<CellState>> = vec![vec![]];
+        let mut run: usize = 0;
+        let mut prefix: Option<char> = None;
+
+        let mut lines = rle.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        if let Some(header) = lines.next() {
+            if !header.starts_with('x') {
+                return Err(String::from("Missing RLE header"));
+            }
+            // The rule runs to the end of the line, Larger than Life rulestrings holding commas
+            let (sizes, rule_field) = match header.find("rule") {
+                Some(start) => (&header[..start], Some(&header[start..])),
+                None => (header, None),
+            };
+            if let Some(rule_field) = rule_field {
+                let (_, value) = rule_field.split_once('=').ok_or(format!("Invalid RLE header field '{}'", rule_field))?;
+                rule = Some(value.trim().to_string());
+            }
+            for field in sizes.split(',').filter(|field| !field.trim().is_empty()) {
+                let (key, value) = field.split_once('=').ok_or(format!("Invalid RLE header field '{}'", field))?;
+                match key.trim() {
+                    "x" => width = value.trim().parse().map_err(|_| format!("Invalid RLE width '{}'", value))?,
+                    "y" => height = value.trim().parse().map_err(|_| format!("Invalid RLE height '{}'", value))?,
+                    _ => {}
+                }
+            }
+        }
+
+        'body: for line in lines {
+            for tag in line.chars().filter(|c| !c.is_whitespace()) {
+                let count = run.max(1);
+                let state = match (prefix, tag) {
+                    (None, '0'..='9') => {
+                        run = run * 10 + tag.to_digit(10).unwrap() as usize;
+                        continue;
+                    }
+                    (None, 'p'..='y') => {
+                        prefix = Some(tag);
+                        continue;
+                    }
+                    (None, '!') => break 'body,
+                    (None, '$') => {
+                        for _ in 0..count {
+                            states.push(vec![]);
+                        }
+                        run = 0;
+                        continue;
+                    }
+                    (None, 'b') | (None, '.') => 0,
+                    (None, 'o') => 1,
+                    (None, 'A'..='X') => tag as usize - 'A' as usize + 1,
+                    (Some(high), 'A'..='X') => (high as usize - 'p' as usize + 1) * 24 + tag as usize - 'A' as usize + 1,
+                    _ => return Err(format!("Invalid RLE tag '{}'", tag))
+                };
+                if state > u8::MAX as usize {
+                    return Err(format!("RLE state {} is out of range", state));
+                }
+
+                let line = states.last_mut().unwrap();
+                for _ in 0..count {
+                    line.push(CellState::from_index(state as u8));
+                }
+                run = 0;
+                prefix = None;
+            }
+        }
+
+        while states.len() < height {
+            states.push(vec![]);
+        }
+        let mut pattern = Pattern::new(states);
+        if pattern.width() < width {
+            pattern.states.iter_mut().for_each(|line| line.resize(width, CellState::DEAD));
+        }
+        pattern.rule = rule;
+        Ok(pattern)
+    }
+
+    pub fn to_rle(&self) -> String {
+        let is_two_states = self.states.iter().flatten().all(|state| state.index() <= 1);
+        let tag_of = |state: &CellState| match (is_two_states, state.index()) {
+            (true, 0) => String::from("b"),
+            (true, _) => String::from("o"),
+            (false, 0) => String::from("."),
+            (false, index) if index <= 24 => ((b'A' + index - 1) as char).to_string(),
+            (false, index) => format!("{}{}", (b'p' + (index - 1) / 24 - 1) as char, (b'A' + (index - 1) % 24) as char),
+        };
+
+        let mut tokens: Vec<String> = vec![];
+        let mut pending_lines = 0;
+        for line in &self.states {
+            let end = line.iter().rposition(|state| state != &CellState::DEAD).map_or(0, |last| last + 1);
+            if end > 0 && pending_lines > 0 {
+                tokens.push(Self::run_of(pending_lines, "$"));
+                pending_lines = 0;
+            }
+
+            let mut x = 0;
+            while x < end {
+                let run = line[x..end].iter().take_while(|state| *state == &line[x]).count();
+                tokens.push(Self::run_of(run, &tag_of(&line[x])));
+                x += run;
+            }
+            pending_lines += 1;
+        }
+        tokens.push(String::from("!"));
+
+        let mut rle = format!("x = {}, y = {}", self.width(), self.height());
+        if let Some(rule) = &self.rule {
+            rle.push_str(&format!(", rule = {}", rule));
+        }
+        rle.push('\n');
+
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length + token.len() > RLE_LINE_LENGTH {
+                rle.push('\n');
+                line_length = 0;
+            }
+            line_length += token.len();
+            rle.push_str(&token);
+        }
+        rle.push('\n');
+        rle
+    }
+
+    fn run_of(count: usize, tag: &str) -> String {
+        match count {
+            1 => tag.to_string(),
+            n => format!("{}{}", n, tag)
+        }
+    }
+}
+
+#[cfg(test)]
+mod pattern_tests {
+    use super::*;
+
+    #[test]
+    fn should_read_two_states_rle() {
+        let glider = Pattern::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!\n").unwrap();
+
+        assert_eq!(glider.rule(), Some("B3/S23"));
+        assert_eq!(glider.states(), &vec![
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD],
+            vec![CellState::DEAD, CellState::DEAD, CellState::ALIVE],
+            vec![CellState::ALIVE, CellState::ALIVE, CellState::ALIVE],
+        ]);
+    }
+
+    #[test]
+    fn should_read_multi_states_rle() {
+        let diode = Pattern::from_rle("x = 5, y = 3, rule = WireWorld\n.2C$BA2C.$.2C!").unwrap();
+
+        assert_eq!(diode.width(), 5);
+        assert_eq!(diode.states()[1], vec![CellState::DYING(1), CellState::ALIVE, CellState::DYING(2), CellState::DYING(2), CellState::DEAD]);
+        assert_eq!(diode.states()[2], vec![CellState::DEAD, CellState::DYING(2), CellState::DYING(2), CellState::DEAD, CellState::DEAD]);
+    }
+
+    #[test]
+    fn should_read_high_states_and_blank_lines() {
+        let pattern = Pattern::from_rle("x = 2, y = 4\npA3$yO!").unwrap();
+
+        assert_eq!(pattern.height(), 4);
+        assert_eq!(pattern.states()[0][0], CellState::from_index(25));
+        assert_eq!(pattern.states()[1], vec![CellState::DEAD, CellState::DEAD]);
+        assert_eq!(pattern.states()[3][0], CellState::from_index(255));
+    }
+
+    #[test]
+    fn should_reject_invalid_rle() {
+        assert!(Pattern::from_rle("bo$ob!").is_err());
+        assert!(Pattern::from_rle("x = 2, y = 2\nbz!").is_err());
+    }
+
+    #[test]
+    fn should_write_rle_that_reads_back() {
+        let glider = Pattern::from_rle("x = 3, y = 3\nbob$2bo$3o!").unwrap();
+        let circuit = Pattern::from_rle("x = 5, y = 4, rule = Wireworld\n.2C$BA2C.2$pA!").unwrap();
+
+        assert_eq!(glider.to_rle(), "x = 3, y = 3\nbo$2bo$3o!\n");
+        assert_eq!(circuit.to_rle(), "x = 5, y = 4, rule = Wireworld\n.2C$BA2C2$pA!\n");
+        assert_eq!(Pattern::from_rle(&circuit.to_rle()).unwrap(), circuit);
+    }
+
+    #[test]
+    fn should_read_back_a_rule_holding_commas() {
+        let bosco = Pattern::from_rle("x = 2, y = 1, rule = R5,C0,M1,S34..58,B34..45,NM
+2o!").unwrap();
+
+        assert_eq!(bosco.rule(), Some("R5,C0,M1,S34..58,B34..45,NM"));
+        assert!(crate::common::rule::from_name(bosco.rule().unwrap()).is_ok());
+        assert_eq!(Pattern::from_rle(&bosco.to_rle()).unwrap(), bosco);
+    }
+
+    #[test]
+    fn should_come_back_after_four_quarter_turns_or_two_flips() {
+        let pattern = Pattern::from_text(&["x x o", "o o x"]);
+
+        let rotated = (0..4).fold(pattern.clone(), |pattern, _| pattern.transformed(Transform::Rotate90));
+
+        assert_eq!(rotated, pattern);
+        assert_eq!(pattern.transformed(Transform::Rotate90).width(), 2);
+        assert_eq!(pattern.transformed(Transform::FlipHorizontal).transformed(Transform::FlipVertical), pattern.transformed(Transform::Rotate180));
+        assert_eq!(pattern.transformed(Transform::FlipDiagonal).states()[2], vec![CellState::DEAD, CellState::ALIVE]);
+    }
+
+    #[test]
+    fn should_center_pattern_in_a_larger_grid() {
+        let pattern = Pattern::new(vec![vec![CellState::ALIVE]]);
+
+        let states = pattern.centered_in(3, 3);
+
+        assert_eq!(states[1][1], CellState::ALIVE);
+        assert_eq!(states.iter().flatten().filter(|state| state.is_alive()).count(), 1);
+    }
+}
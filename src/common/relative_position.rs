@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum RelativePosition {
     North,
     NorthEast,
@@ -9,35 +11,42 @@ pub enum RelativePosition {
     West,
     NorthWest,
     Central,
+    /// Any other neighbour, `x` growing eastward and `y` southward.
+    Offset { x: i32, y: i32 },
 }
 
 impl RelativePosition {
     pub fn get_position_from(x: usize, y: usize, relative_x: usize, relative_y: usize) -> RelativePosition {
-        let x_translation: i32 = x as i32 - relative_x as i32;
-        let y_translation = y as i32 - relative_y as i32;
-
-        match y_translation {
-            -1 => {
-                match x_translation {
-                    -1 => RelativePosition::SouthEast,
-                    0 => RelativePosition::South,
-                    1 | _ => RelativePosition::SouthWest
-                }
-            },
-            0 => {
-                match x_translation {
-                    -1 => RelativePosition::East,
-                    0 => RelativePosition::Central,
-                    1 | _ => RelativePosition::West,
-                }
-            },
-            1 | _ => {
-                match x_translation {
-                    -1 => RelativePosition::NorthEast,
-                    0 => RelativePosition::North,
-                    1 | _ => RelativePosition::NorthWest
-                }
-            }
+        RelativePosition::from_offset(relative_x as i32 - x as i32, relative_y as i32 - y as i32)
+    }
+
+    pub fn from_offset(x: i32, y: i32) -> RelativePosition {
+        match (x, y) {
+            (-1, -1) => RelativePosition::NorthWest,
+            (0, -1) => RelativePosition::North,
+            (1, -1) => RelativePosition::NorthEast,
+            (-1, 0) => RelativePosition::West,
+            (0, 0) => RelativePosition::Central,
+            (1, 0) => RelativePosition::East,
+            (-1, 1) => RelativePosition::SouthWest,
+            (0, 1) => RelativePosition::South,
+            (1, 1) => RelativePosition::SouthEast,
+            (x, y) => RelativePosition::Offset { x, y },
+        }
+    }
+
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            RelativePosition::North => (0, -1),
+            RelativePosition::NorthEast => (1, -1),
+            RelativePosition::East => (1, 0),
+            RelativePosition::SouthEast => (1, 1),
+            RelativePosition::South => (0, 1),
+            RelativePosition::SouthWest => (-1, 1),
+            RelativePosition::West => (-1, 0),
+            RelativePosition::NorthWest => (-1, -1),
+            RelativePosition::Central => (0, 0),
+            RelativePosition::Offset { x, y } => (*x, *y),
         }
     }
 
@@ -52,6 +61,37 @@ impl RelativePosition {
             RelativePosition::West => String::from("W"),
             RelativePosition::NorthWest => String::from("NW"),
             RelativePosition::Central => String::from("C"),
+            RelativePosition::Offset { x, y } => format!("x{:+}y{:+}", x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod relative_position_tests {
+    use super::*;
+
+    #[test]
+    fn should_name_adjacent_positions() {
+        assert_eq!(RelativePosition::get_position_from(1, 1, 2, 1), RelativePosition::East);
+        assert_eq!(RelativePosition::get_position_from(1, 1, 0, 0), RelativePosition::NorthWest);
+        assert_eq!(RelativePosition::get_position_from(1, 1, 1, 2), RelativePosition::South);
+    }
+
+    #[test]
+    fn should_keep_offset_of_distant_positions() {
+        let position = RelativePosition::get_position_from(3, 3, 1, 4);
+
+        assert_eq!(position, RelativePosition::Offset { x: -2, y: 1 });
+        assert_eq!(position.offset(), (-2, 1));
+        assert_eq!(position.print(), "x-2y+1");
+    }
+
+    #[test]
+    fn should_read_back_offset_of_every_position() {
+        for x in -2..=2 {
+            for y in -2..=2 {
+                assert_eq!(RelativePosition::from_offset(x, y).offset(), (x, y));
+            }
         }
     }
-}
\ No newline at end of file
+}
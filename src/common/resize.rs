@@ -0,0 +1,128 @@
+use crate::common::topology::Topology;
+
+/// Part of a universe that stays in place when it is resized, the cells
+/// being added or cut on the opposite sides.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Centre,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far the cells move when a grid goes from one size to the other.
+    pub fn offset(&self, (width, height): (usize, usize), (new_width, new_height): (usize, usize)) -> (i64, i64) {
+        let shift = |size: usize, new_size: usize, at_start: bool, at_end: bool| {
+            let growth = new_size as i64 - size as i64;
+            match (at_start, at_end) {
+                (true, _) => 0,
+                (_, true) => growth,
+                _ => growth / 2,
+            }
+        };
+        let (left, right) = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => (true, false),
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => (false, true),
+            _ => (false, false),
+        };
+        let (top, bottom) = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => (true, false),
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => (false, true),
+            _ => (false, false),
+        };
+        (shift(width, new_width, left, right), shift(height, new_height, top, bottom))
+    }
+}
+
+/// Grid of the given size where every cell moved by the offset, the ones
+/// moved outside of it being dropped. Cells are built from the one moved
+/// there, if any.
+pub fn moved<T, U>(grid: Vec<Vec<T>>, (width, height): (usize, usize), (offset_x, offset_y): (i64, i64), mut new_cell: impl FnMut(usize, usize, Option<T>) -> U) -> Vec<Vec<U>> {
+    let mut grid = grid
+        .into_iter()
+        .map(|line| line.into_iter().map(Some).collect::<Vec<Option<T>>>())
+        .collect::<Vec<Vec<Option<T>>>>();
+
+    (0..height)
+        .map(|y| (0..width)
+            .map(|x| {
+                let former_x = usize::try_from(x as i64 - offset_x).ok();
+                let former_y = usize::try_from(y as i64 - offset_y).ok();
+                let former = former_y
+                    .zip(former_x)
+                    .and_then(|(former_y, former_x)| grid.get_mut(former_y)?.get_mut(former_x)?.take());
+                new_cell(x, y, former)
+            })
+            .collect()
+        )
+        .collect()
+}
+
+/// Size a grid grows to, and how far its cells move, for at least the given
+/// number of cells to lie beyond any edge alive cells are within range of.
+/// Spheres grow on their right or bottom side as well to stay square. None
+/// when no alive cell is that close to an edge.
+pub fn expansion(alive: impl IntoIterator<Item=(usize, usize)>, (width, height): (usize, usize), range: usize, cells: usize, topology: &Topology) -> Option<((usize, usize), (i64, i64))> {
+    let cells = cells.max(range);
+    let (mut left, mut top, mut right, mut bottom) = (0, 0, 0, 0);
+    for (x, y) in alive {
+        if x < range {
+            left = cells;
+        }
+        if y < range {
+            top = cells;
+        }
+        if x + range >= width {
+            right = cells;
+        }
+        if y + range >= height {
+            bottom = cells;
+        }
+    }
+    if left + top + right + bottom == 0 {
+        return None;
+    }
+
+    let (mut new_width, mut new_height) = (width + left + right, height + top + bottom);
+    if *topology == Topology::Sphere {
+        (new_width, new_height) = (new_width.max(new_height), new_width.max(new_height));
+    }
+    Some(((new_width, new_height), (left as i64, top as i64)))
+}
+
+#[cfg(test)]
+mod resize_tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_the_anchored_side_in_place() {
+        assert_eq!(Anchor::TopLeft.offset((4, 4), (8, 6)), (0, 0));
+        assert_eq!(Anchor::BottomRight.offset((4, 4), (8, 6)), (4, 2));
+        assert_eq!(Anchor::Centre.offset((4, 4), (8, 7)), (2, 1));
+        assert_eq!(Anchor::Right.offset((4, 4), (2, 2)), (-2, -1));
+    }
+
+    #[test]
+    fn should_move_cells_and_fill_the_new_ones() {
+        let grid = vec![vec![1, 2], vec![3, 4]];
+
+        let moved = moved(grid, (3, 2), (1, -1), |_, _, former| former.unwrap_or(0));
+
+        assert_eq!(moved, vec![vec![0, 3, 4], vec![0, 0, 0]]);
+    }
+
+    #[test]
+    fn should_only_expand_towards_alive_cells_within_range_of_an_edge() {
+        assert_eq!(expansion([(5, 5)], (10, 10), 1, 4, &Topology::Plane), None);
+        assert_eq!(expansion([(0, 5)], (10, 10), 1, 4, &Topology::Plane), Some(((14, 10), (4, 0))));
+        assert_eq!(expansion([(5, 8)], (10, 10), 2, 1, &Topology::Plane), Some(((10, 12), (0, 0))));
+        assert_eq!(expansion([(0, 5)], (10, 10), 1, 4, &Topology::Sphere), Some(((14, 14), (4, 0))));
+    }
+}
@@ -0,0 +1,278 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::common::cell_state::{CellState, MAX_DYING_STAGE};
+use crate::common::hensel::HenselRule;
+use crate::common::larger_than_life::LargerThanLifeRule;
+use crate::common::multi_colour::MultiColourRule;
+use crate::common::neighbourhood::Neighbourhood;
+use crate::common::relative_position::RelativePosition;
+use crate::common::stochastic::StochasticRule;
+use crate::common::wireworld::Wireworld;
+
+/// Transition function of an automaton: the next state of a cell given its
+/// current state and the states of its neighbours, each one tagged with its
+/// position relative to the cell.
+pub trait Rule: fmt::Debug {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState;
+
+    /// Rulestring or name the rule can be read back from with `from_name`.
+    fn name(&self) -> String;
+
+    /// Neighbourhood the rule is defined on, universes keeping theirs otherwise.
+    fn neighbourhood(&self) -> Option<Neighbourhood> {
+        None
+    }
+
+    /// The rule itself when it only depends on the number of alive neighbours,
+    /// letting universes count them faster than by listing them.
+    fn as_totalistic(&self) -> Option<&dyn TotalisticRule> {
+        None
+    }
+
+    /// The rule itself when it adds noise to another one, which its name leaves out.
+    fn as_stochastic(&self) -> Option<&StochasticRule> {
+        None
+    }
+
+    /// Number of colours alive cells can take.
+    fn colours(&self) -> u8 {
+        1
+    }
+
+    /// Whether the same neighbourhood always leads to the same next state,
+    /// which lets universes skip the cells whose neighbourhood did not change.
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+
+    /// State numbered `index` in RLE files written for this rule, Golly
+    /// numbering states differently from one kind of rule to the other.
+    fn state_of_index(&self, index: u8) -> CellState {
+        CellState::from_index(index)
+    }
+}
+
+pub trait TotalisticRule {
+    fn next_state_from_count(&self, state: &CellState, alive_neighbours: usize) -> CellState;
+}
+
+/// Built-in automata by name (such as `Wireworld` or `QuadLife`), any other name being read
+/// as a Larger than Life rulestring when it starts with a range, otherwise as
+/// a Life-like one, or in Hensel notation when it is not totalistic.
+pub fn from_name(name: &str) -> Result<Rc<dyn Rule>, String> {
+    match name.trim().to_ascii_lowercase().as_str() {
+        "wireworld" => Ok(Rc::new(Wireworld)),
+        "immigration" => Ok(Rc::new(MultiColourRule::immigration())),
+        "quadlife" => Ok(Rc::new(MultiColourRule::quad_life())),
+        name if name.starts_with('r') => Ok(Rc::new(LargerThanLifeRule::parse(name)?)),
+        _ => match LifeLikeRule::parse(name) {
+            Ok(rule) => Ok(Rc::new(rule)),
+            Err(_) => Ok(Rc::new(HenselRule::parse(name)?))
+        }
+    }
+}
+
+/// Life-like rule extended with the number of states of Generations rules,
+/// such as `B3/S23` (Conway's Life), `B2/S/C3` (Brian's Brain) or
+/// `B2/S345/C4` (Star Wars).
+#[derive(Debug, PartialEq, Clone)]
+pub struct LifeLikeRule {
+    birth: Vec<usize>,
+    survival: Vec<usize>,
+    states: u8,
+}
+
+impl LifeLikeRule {
+    pub fn conway() -> LifeLikeRule {
+        LifeLikeRule {
+            birth: vec![3],
+            survival: vec![2, 3],
+            states: 2,
+        }
+    }
+
+    /// Reads `B3/S23` and `B2/S345/C4` rulestrings (in any order, `G` being
+    /// accepted for `C`) as well as Golly's `S/B` and `S/B/C` forms such as `23/3`.
+    pub fn parse(rulestring: &str) -> Result<LifeLikeRule, String> {
+        let parts = rulestring.trim().split('/').collect::<Vec<&str>>();
+        let mut birth: Option<Vec<usize>> = None;
+        let mut survival: Option<Vec<usize>> = None;
+        let mut states: u8 = 2;
+
+        let is_prefixed = parts.iter().all(|part| part.starts_with(|c: char| c.is_ascii_alphabetic()));
+        if is_prefixed {
+            for part in &parts {
+                let (prefix, value) = part.split_at(1);
+                match prefix.to_ascii_uppercase().as_str() {
+                    "B" if birth.is_none() => birth = Some(Self::parse_counts(value, rulestring)?),
+                    "S" if survival.is_none() => survival = Some(Self::parse_counts(value, rulestring)?),
+                    "C" | "G" => states = Self::parse_states(value, rulestring)?,
+                    _ => return Err(format!("Invalid rule '{}'", rulestring))
+                }
+            }
+        } else {
+            match parts.as_slice() {
+                [s, b] => {
+                    survival = Some(Self::parse_counts(s, rulestring)?);
+                    birth = Some(Self::parse_counts(b, rulestring)?);
+                }
+                [s, b, c] => {
+                    survival = Some(Self::parse_counts(s, rulestring)?);
+                    birth = Some(Self::parse_counts(b, rulestring)?);
+                    states = Self::parse_states(c, rulestring)?;
+                }
+                _ => return Err(format!("Invalid rule '{}'", rulestring))
+            }
+        }
+
+        match (birth, survival) {
+            (Some(birth), Some(survival)) => Ok(LifeLikeRule { birth, survival, states }),
+            _ => Err(format!("Rule '{}' needs both birth and survival conditions", rulestring))
+        }
+    }
+
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    fn decay(&self, stage: u8) -> CellState {
+        // States are numbered as in Golly: 0 dead, 1 alive, 2 and above dying
+        if stage + 2 < self.states {
+            CellState::DYING(stage + 1)
+        } else {
+            CellState::DEAD
+        }
+    }
+
+    fn parse_counts(counts: &str, rulestring: &str) -> Result<Vec<usize>, String> {
+        counts
+            .chars()
+            .map(|count| match count.to_digit(10) {
+                Some(count) if count <= 8 => Ok(count as usize),
+                _ => Err(format!("Invalid neighbour count '{}' in rule '{}'", count, rulestring))
+            })
+            .collect()
+    }
+
+    fn parse_states(states: &str, rulestring: &str) -> Result<u8, String> {
+        match states.parse::<u8>() {
+            Ok(states) if (2..=MAX_DYING_STAGE + 2).contains(&states) => Ok(states),
+            _ => Err(format!("Invalid number of states '{}' in rule '{}'", states, rulestring))
+        }
+    }
+}
+
+impl Default for LifeLikeRule {
+    fn default() -> LifeLikeRule {
+        LifeLikeRule::conway()
+    }
+}
+
+impl fmt::Display for LifeLikeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |counts: &Vec<usize>| counts.iter().map(|count| count.to_string()).collect::<String>();
+        write!(f, "B{}/S{}", counts(&self.birth), counts(&self.survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+impl TotalisticRule for LifeLikeRule {
+    fn next_state_from_count(&self, state: &CellState, alive_neighbours: usize) -> CellState {
+        match state {
+            CellState::ALIVE | CellState::COLOURED(_) if self.survival.contains(&alive_neighbours) => state.clone(),
+            CellState::ALIVE | CellState::COLOURED(_) => self.decay(0),
+            CellState::DYING(stage) => self.decay(*stage),
+            CellState::DEAD if self.birth.contains(&alive_neighbours) => CellState::ALIVE,
+            CellState::DEAD | CellState::STATE(_) => CellState::DEAD,
+        }
+    }
+}
+
+impl Rule for LifeLikeRule {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        let alive_neighbours = neighbours.iter().filter(|(state, _)| state.is_alive()).count();
+        self.next_state_from_count(state, alive_neighbours)
+    }
+
+    fn name(&self) -> String {
+        self.to_string()
+    }
+
+    fn as_totalistic(&self) -> Option<&dyn TotalisticRule> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_life_like_rules() {
+        assert_eq!(LifeLikeRule::parse("B3/S23").unwrap(), LifeLikeRule::conway());
+        assert_eq!(LifeLikeRule::parse("s23/b3").unwrap(), LifeLikeRule::conway());
+        assert_eq!(LifeLikeRule::parse("23/3").unwrap(), LifeLikeRule::conway());
+    }
+
+    #[test]
+    fn should_parse_generations_rules() {
+        let brians_brain = LifeLikeRule::parse("B2/S/C3").unwrap();
+        let star_wars = LifeLikeRule::parse("345/2/4").unwrap();
+
+        assert_eq!(brians_brain, LifeLikeRule { birth: vec![2], survival: vec![], states: 3 });
+        assert_eq!(star_wars, LifeLikeRule { birth: vec![2], survival: vec![3, 4, 5], states: 4 });
+        assert_eq!(star_wars.to_string(), "B2/S345/C4");
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        assert!(LifeLikeRule::parse("B9/S23").is_err());
+        assert!(LifeLikeRule::parse("B3").is_err());
+        assert!(LifeLikeRule::parse("B3/S23/C1").is_err());
+        assert!(LifeLikeRule::parse("B3/S23/C38").is_err());
+        assert!(LifeLikeRule::parse("X3/S23").is_err());
+        assert!(LifeLikeRule::parse("3").is_err());
+    }
+
+    #[test]
+    fn should_go_through_refractory_states_when_not_surviving() {
+        let star_wars = LifeLikeRule::parse("B2/S345/C4").unwrap();
+
+        assert_eq!(star_wars.next_state_from_count(&CellState::ALIVE, 3), CellState::ALIVE);
+        assert_eq!(star_wars.next_state_from_count(&CellState::ALIVE, 1), CellState::DYING(1));
+        assert_eq!(star_wars.next_state_from_count(&CellState::DYING(1), 2), CellState::DYING(2));
+        assert_eq!(star_wars.next_state_from_count(&CellState::DYING(2), 2), CellState::DEAD);
+        assert_eq!(star_wars.next_state_from_count(&CellState::DEAD, 2), CellState::ALIVE);
+    }
+
+    #[test]
+    fn should_die_immediately_with_two_states() {
+        assert_eq!(LifeLikeRule::conway().next_state_from_count(&CellState::ALIVE, 1), CellState::DEAD);
+    }
+
+    #[test]
+    fn should_count_alive_neighbours_only() {
+        let neighbours = vec![
+            (CellState::ALIVE, RelativePosition::North),
+            (CellState::DYING(1), RelativePosition::East),
+            (CellState::ALIVE, RelativePosition::South),
+            (CellState::ALIVE, RelativePosition::West),
+        ];
+
+        assert_eq!(LifeLikeRule::conway().next_state(&CellState::DEAD, &neighbours), CellState::ALIVE);
+    }
+
+    #[test]
+    fn should_find_rules_by_name() {
+        assert_eq!(from_name("Wireworld").unwrap().name(), "Wireworld");
+        assert_eq!(from_name("QuadLife").unwrap().name(), "QuadLife");
+        assert_eq!(from_name("23/3").unwrap().name(), "B3/S23");
+        assert_eq!(from_name("B2-a/S12").unwrap().name(), "B2-a/S12");
+        assert_eq!(from_name("R5,C0,M1,S34..58,B34..45,NM").unwrap().name(), "R5,C0,M1,S34..58,B34..45,NM");
+        assert!(from_name("Brian").is_err());
+    }
+}
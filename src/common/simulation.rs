@@ -0,0 +1,20 @@
+use crate::common::cell_age::CellAge;
+use crate::common::cell_state::CellState;
+use crate::common::statistics::GenerationStatistics;
+
+/// What the command line (and any tool driving a run) needs from an engine,
+/// whatever the way it stores its cells.
+pub trait Simulation {
+    fn advance(&mut self);
+
+    fn states(&self) -> Vec<Vec<CellState>>;
+
+    fn statistics(&self) -> GenerationStatistics;
+
+    fn print(&self) -> Vec<String>;
+
+    /// Ages of the cells, for engines tracking them.
+    fn ages(&self) -> Option<Vec<Vec<CellAge>>> {
+        None
+    }
+}
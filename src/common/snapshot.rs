@@ -0,0 +1,238 @@
+use std::fs;
+use std::rc::Rc;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::common::cell_age::CellAge;
+use crate::common::cell_state::CellState;
+use crate::common::history::History;
+use crate::common::neighbourhood::Neighbourhood;
+use crate::common::rule::{self, Rule};
+use crate::common::stochastic::StochasticRule;
+use crate::common::topology::Topology;
+use crate::common::update_mode::UpdateMode;
+
+/// Point a random number generator has reached, which it goes on from once
+/// read back.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub word_position: u128,
+}
+
+impl RngState {
+    pub fn of(rng: &ChaCha12Rng) -> RngState {
+        RngState { seed: rng.get_seed(), word_position: rng.get_word_pos() }
+    }
+
+    pub fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::from_seed(self.seed);
+        rng.set_word_pos(self.word_position);
+        rng
+    }
+}
+
+/// Probabilities and generator of a stochastic rule, which its name leaves out.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Noise {
+    pub birth_probability: f64,
+    pub survival_probability: f64,
+    pub rng: RngState,
+}
+
+impl Noise {
+    pub fn of(rule: &StochasticRule) -> Noise {
+        Noise {
+            birth_probability: rule.birth_probability(),
+            survival_probability: rule.survival_probability(),
+            rng: RngState::of(&rule.rng()),
+        }
+    }
+}
+
+/// Full state of a universe of either engine, to be saved and resumed later.
+/// The rule, neighbourhood, topology and update mode are kept under the names
+/// they are read back from, stochastic rules as the rule they add noise to
+/// along with that noise.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub width: usize,
+    pub height: usize,
+    pub rule: String,
+    pub neighbourhood: String,
+    pub topology: String,
+    pub update_mode: String,
+    pub generation: usize,
+    pub births: usize,
+    pub deaths: usize,
+    pub rng: RngState,
+    pub noise: Option<Noise>,
+    pub history: History,
+    pub expansion: Option<usize>,
+    pub cells: Vec<Vec<CellState>>,
+    pub ages: Option<Vec<Vec<CellAge>>>,
+    /// Generation before the current one, which only second-order universes remember.
+    pub previous_states: Option<Vec<Vec<CellState>>>,
+}
+
+impl Snapshot {
+    /// Rule, neighbourhood, topology and update mode read back from their
+    /// names, once every grid and the history checked to be of the size of
+    /// the universe, and ages checked to count from 1.
+    pub fn settings(&self) -> Result<(Rc<dyn Rule>, Neighbourhood, Topology, UpdateMode), String> {
+        if self.width == 0 || self.height == 0 {
+            return Err(format!("Invalid snapshot of a {}x{} universe", self.width, self.height));
+        }
+        let has_size = |lines: usize, line_lengths: Vec<usize>| lines == self.height && line_lengths.iter().all(|length| *length == self.width);
+        let grids = [
+            Some(has_size(self.cells.len(), self.cells.iter().map(|line| line.len()).collect())),
+            self.ages.as_ref().map(|ages| has_size(ages.len(), ages.iter().map(|line| line.len()).collect())),
+            self.previous_states.as_ref().map(|states| has_size(states.len(), states.iter().map(|line| line.len()).collect())),
+        ];
+        if grids.into_iter().flatten().any(|has_size| !has_size) || !self.history.fits(self.width, self.height) {
+            return Err(format!("Cells of the snapshot do not fit a {}x{} universe", self.width, self.height));
+        }
+        if self.ages.iter().flatten().flatten().any(|age| matches!(age, CellAge::Alive(0) | CellAge::Dead(0))) {
+            return Err(String::from("Ages of the snapshot start from 1 generation"));
+        }
+
+        let topology = Topology::parse(&self.topology)?;
+        topology.check_size(self.width, self.height)?;
+        let rule = rule::from_name(&self.rule)?;
+        let rule: Rc<dyn Rule> = match &self.noise {
+            Some(noise) => Rc::new(StochasticRule::new(rule, noise.birth_probability, noise.survival_probability).with_rng(noise.rng.restore())),
+            None => rule,
+        };
+        Ok((
+            rule,
+            Neighbourhood::parse(&self.neighbourhood)?,
+            topology,
+            UpdateMode::parse(&self.update_mode)?,
+        ))
+    }
+}
+
+pub fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|error| error.to_string())
+}
+
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|error| format!("Invalid JSON snapshot: {}", error))
+}
+
+/// Compact binary format, one byte standing for most cells.
+pub fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    postcard::to_stdvec(value).map_err(|error| error.to_string())
+}
+
+pub fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    postcard::from_bytes(bytes).map_err(|error| format!("Invalid binary snapshot: {}", error))
+}
+
+/// Writes a file in JSON when its name ends in `.json`, in the binary format otherwise.
+pub fn save<T: Serialize>(value: &T, path: &str) -> Result<(), String> {
+    let content = match path.ends_with(".json") {
+        true => to_json(value)?.into_bytes(),
+        false => to_bytes(value)?,
+    };
+    fs::write(path, content).map_err(|error| format!("Could not write {}: {}", path, error))
+}
+
+/// Reads a file written by `save`.
+pub fn load<T: DeserializeOwned>(path: &str) -> Result<T, String> {
+    let content = fs::read(path).map_err(|error| format!("Could not read {}: {}", path, error))?;
+    match path.ends_with(".json") {
+        true => from_json(&String::from_utf8(content).map_err(|error| format!("Invalid JSON snapshot: {}", error))?),
+        false => from_bytes(&content),
+    }
+}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use super::*;
+    use crate::common::history::GenerationDelta;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            width: 3,
+            height: 2,
+            rule: String::from("B2/S/C3"),
+            neighbourhood: String::from("moore"),
+            topology: String::from("torus"),
+            update_mode: String::from("sync"),
+            generation: 7,
+            births: 1,
+            deaths: 2,
+            rng: RngState::of(&ChaCha12Rng::seed_from_u64(42)),
+            noise: None,
+            history: History::default(),
+            expansion: None,
+            cells: vec![vec![CellState::ALIVE, CellState::DEAD, CellState::DYING(1)], vec![CellState::DEAD; 3]],
+            ages: None,
+            previous_states: None,
+        }
+    }
+
+    #[test]
+    fn should_read_back_what_it_wrote_in_both_formats() {
+        let snapshot = snapshot();
+
+        let json = to_json(&snapshot).unwrap();
+        let bytes = to_bytes(&snapshot).unwrap();
+
+        assert!(json.contains(r#""rule":"B2/S/C3""#));
+        assert!(json.contains(r#"[["ALIVE","DEAD",{"DYING":1}],["DEAD","DEAD","DEAD"]]"#));
+        assert!(bytes.len() < json.len() / 4);
+        assert_eq!(from_json::<Snapshot>(&json).unwrap(), snapshot);
+        assert_eq!(from_bytes::<Snapshot>(&bytes).unwrap(), snapshot);
+        assert!(from_bytes::<Snapshot>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn should_only_accept_cells_of_the_size_of_the_universe() {
+        let mut snapshot = snapshot();
+        assert!(snapshot.settings().is_ok());
+
+        snapshot.ages = Some(vec![vec![CellAge::NeverAlive; 3]]);
+        assert!(snapshot.settings().is_err());
+
+        snapshot.ages = Some(vec![vec![CellAge::Alive(1), CellAge::NeverAlive, CellAge::Dead(0)], vec![CellAge::NeverAlive; 3]]);
+        assert_eq!(snapshot.settings().err(), Some(String::from("Ages of the snapshot start from 1 generation")));
+        assert!(from_json::<np::Universe>(&to_json(&snapshot).unwrap()).is_err());
+
+        snapshot.ages = Some(vec![vec![CellAge::Alive(1), CellAge::NeverAlive, CellAge::Dead(3)], vec![CellAge::NeverAlive; 3]]);
+        assert!(snapshot.settings().is_ok());
+
+        snapshot.ages = None;
+        snapshot.history = History::new(1);
+        snapshot.history.record(GenerationDelta::between(6, 0, 1, &[vec![CellState::ALIVE; 4]], &[vec![CellState::DEAD; 4]]));
+        assert!(snapshot.settings().is_err());
+
+        snapshot.history = History::default();
+        snapshot.topology = String::from("donut");
+        assert_eq!(snapshot.settings().err(), Some(String::from("Invalid topology 'donut'")));
+
+        snapshot.topology = String::from("sphere");
+        assert!(snapshot.settings().is_err());
+    }
+
+    #[test]
+    fn should_resume_a_universe_saved_by_one_engine_on_the_other() {
+        let np_universe = np::Universe::new_with_seed(12, 12, 3).with_topology(Topology::KleinBottle).tick().tick();
+        let path = std::env::temp_dir().join("kata_game_of_life_snapshot_test.json");
+        let path = path.to_string_lossy();
+
+        save(&np_universe, &path).unwrap();
+        let sp_universe: sp::Universe = load(&path).unwrap();
+        std::fs::remove_file(path.as_ref()).unwrap();
+
+        assert_eq!(sp_universe.statistics(), np_universe.statistics());
+        sp_universe.tick();
+        assert_eq!(sp_universe.print(), np_universe.tick().print());
+    }
+}
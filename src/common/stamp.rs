@@ -0,0 +1,174 @@
+use std::fmt;
+
+use crate::common::cell_state::CellState;
+use crate::common::pattern::Pattern;
+use crate::common::transform::Transform;
+
+/// How the cells of a stamped pattern are combined with the ones under them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum CombineMode {
+    /// The pattern replaces everything under it, dead cells included.
+    #[default]
+    Overwrite,
+    /// Alive cells of the pattern are added, the others left as they were.
+    Or,
+    /// Alive cells of the pattern bring dead cells to life and kill alive ones.
+    Xor,
+    /// Alive cells of the pattern kill the cells under them.
+    AndNot,
+}
+
+impl CombineMode {
+    pub fn parse(mode: &str) -> Result<CombineMode, String> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "overwrite" => Ok(CombineMode::Overwrite),
+            "or" => Ok(CombineMode::Or),
+            "xor" => Ok(CombineMode::Xor),
+            "and-not" => Ok(CombineMode::AndNot),
+            _ => Err(format!("Invalid combine mode '{}'", mode))
+        }
+    }
+
+    pub fn combine(&self, existing: &CellState, stamped: &CellState) -> CellState {
+        match (self, stamped.is_alive()) {
+            (CombineMode::Overwrite, _) | (CombineMode::Or, true) => stamped.clone(),
+            (CombineMode::Xor, true) if existing.is_alive() => CellState::DEAD,
+            (CombineMode::Xor, true) => stamped.clone(),
+            (CombineMode::AndNot, true) => CellState::DEAD,
+            (_, false) => existing.clone(),
+        }
+    }
+}
+
+impl fmt::Display for CombineMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CombineMode::Overwrite => write!(f, "overwrite"),
+            CombineMode::Or => write!(f, "or"),
+            CombineMode::Xor => write!(f, "xor"),
+            CombineMode::AndNot => write!(f, "and-not"),
+        }
+    }
+}
+
+/// Pattern to place into a universe, with its top-left corner at an offset
+/// that may lie beyond the edges, the part of it outside being left out.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stamp {
+    pattern: Pattern,
+    x: i64,
+    y: i64,
+    transform: Transform,
+    mode: CombineMode,
+}
+
+impl Stamp {
+    pub fn new(pattern: Pattern) -> Stamp {
+        Stamp {
+            pattern,
+            x: 0,
+            y: 0,
+            transform: Transform::default(),
+            mode: CombineMode::default(),
+        }
+    }
+
+    pub fn at(self, x: i64, y: i64) -> Stamp {
+        Stamp {
+            x,
+            y,
+            ..self
+        }
+    }
+
+    /// Rotates or reflects the pattern before placing it, its top-left corner staying at the offset.
+    pub fn with_transform(self, transform: Transform) -> Stamp {
+        Stamp {
+            transform,
+            ..self
+        }
+    }
+
+    pub fn with_mode(self, mode: CombineMode) -> Stamp {
+        Stamp {
+            mode,
+            ..self
+        }
+    }
+
+    /// Cells of the grid the stamp changes, with their new states.
+    pub fn changes(&self, states: &[Vec<CellState>]) -> Vec<(usize, usize, CellState)> {
+        let (width, height) = (self.pattern.width(), self.pattern.height());
+        let mut changes = vec![];
+
+        for (y, line) in self.pattern.states().iter().enumerate() {
+            for (x, stamped) in line.iter().enumerate() {
+                let (offset_x, offset_y) = self.transform.apply(x, y, width, height);
+                let (grid_x, grid_y) = (self.x + offset_x as i64, self.y + offset_y as i64);
+                let existing = match (usize::try_from(grid_x), usize::try_from(grid_y)) {
+                    (Ok(grid_x), Ok(grid_y)) => states.get(grid_y).and_then(|line| line.get(grid_x)).map(|state| (grid_x, grid_y, state)),
+                    _ => None,
+                };
+
+                if let Some((grid_x, grid_y, existing)) = existing {
+                    let combined = self.mode.combine(existing, stamped);
+                    if &combined != existing {
+                        changes.push((grid_x, grid_y, combined));
+                    }
+                }
+            }
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod stamp_tests {
+    use super::*;
+
+    fn grid(lines: &[&str]) -> Vec<Vec<CellState>> {
+        lines.iter().map(|line| line.chars().filter(|cell| cell != &' ').map(CellState::from_char).collect()).collect()
+    }
+
+    fn stamped(stamp: &Stamp, lines: &[&str]) -> Vec<Vec<CellState>> {
+        let mut states = grid(lines);
+        for (x, y, state) in stamp.changes(&states) {
+            states[y][x] = state;
+        }
+        states
+    }
+
+    #[test]
+    fn should_combine_cells_by_mode() {
+        let existing = [CellState::DEAD, CellState::ALIVE];
+        let combined = |mode: CombineMode, stamped: CellState| existing.iter().map(|state| mode.combine(state, &stamped)).collect::<Vec<CellState>>();
+
+        assert_eq!(combined(CombineMode::Overwrite, CellState::DEAD), vec![CellState::DEAD, CellState::DEAD]);
+        assert_eq!(combined(CombineMode::Or, CellState::ALIVE), vec![CellState::ALIVE, CellState::ALIVE]);
+        assert_eq!(combined(CombineMode::Or, CellState::DEAD), vec![CellState::DEAD, CellState::ALIVE]);
+        assert_eq!(combined(CombineMode::Xor, CellState::ALIVE), vec![CellState::ALIVE, CellState::DEAD]);
+        assert_eq!(combined(CombineMode::AndNot, CellState::ALIVE), vec![CellState::DEAD, CellState::DEAD]);
+        assert_eq!(CombineMode::parse("and-not"), Ok(CombineMode::AndNot));
+    }
+
+    #[test]
+    fn should_place_a_rotated_glider_at_an_offset() {
+        let glider = Pattern::from_text(&["o x o", "o o x", "x x x"]);
+        let stamp = Stamp::new(glider).at(1, 1).with_transform(Transform::Rotate90);
+
+        assert_eq!(stamped(&stamp, &["o o o o o", "o o o o o", "o o o o o", "o o o o o"]), grid(&[
+            "o o o o o",
+            "o x o o o",
+            "o x o x o",
+            "o x x o o",
+        ]));
+    }
+
+    #[test]
+    fn should_leave_out_cells_beyond_the_edges() {
+        let block = Pattern::from_text(&["x x", "x x"]);
+        let stamp = Stamp::new(block).at(-1, 2).with_mode(CombineMode::Xor);
+
+        assert_eq!(stamped(&stamp, &["x o", "o o", "x o"]), grid(&["x o", "o o", "o o"]));
+    }
+}
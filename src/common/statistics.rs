@@ -0,0 +1,126 @@
+use crate::common::cell_state::CellState;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BoundingBox {
+    pub min_x: usize,
+    pub min_y: usize,
+    pub max_x: usize,
+    pub max_y: usize,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct GenerationStatistics {
+    pub generation: usize,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+    pub bounding_box: Option<BoundingBox>,
+    pub density: f64,
+}
+
+impl GenerationStatistics {
+    pub fn from_states(generation: usize, births: usize, deaths: usize, states: &[Vec<CellState>]) -> GenerationStatistics {
+        let mut population = 0;
+        let mut bounding_box: Option<BoundingBox> = None;
+
+        for (y, line) in states.iter().enumerate() {
+            for (x, state) in line.iter().enumerate() {
+                if state.is_alive() {
+                    population += 1;
+                    bounding_box = Some(match bounding_box {
+                        None => BoundingBox { min_x: x, min_y: y, max_x: x, max_y: y },
+                        Some(current) => BoundingBox {
+                            min_x: current.min_x.min(x),
+                            min_y: current.min_y.min(y),
+                            max_x: current.max_x.max(x),
+                            max_y: current.max_y.max(y),
+                        }
+                    });
+                }
+            }
+        }
+
+        let number_of_cells: usize = states.iter().map(|line| line.len()).sum();
+        let density = match number_of_cells {
+            0 => 0.0,
+            n => population as f64 / n as f64
+        };
+
+        GenerationStatistics {
+            generation,
+            population,
+            births,
+            deaths,
+            bounding_box,
+            density,
+        }
+    }
+
+    pub fn csv_header() -> String {
+        String::from("generation,population,births,deaths,min_x,min_y,max_x,max_y,density")
+    }
+
+    pub fn to_csv(&self) -> String {
+        let bounding_box = match &self.bounding_box {
+            Some(bounding_box) => format!("{},{},{},{}", bounding_box.min_x, bounding_box.min_y, bounding_box.max_x, bounding_box.max_y),
+            None => String::from(",,,")
+        };
+        format!("{},{},{},{},{},{:.4}", self.generation, self.population, self.births, self.deaths, bounding_box, self.density)
+    }
+}
+
+/// Births and deaths between two generations of the same grid.
+pub fn count_transitions(previous: &[Vec<CellState>], current: &[Vec<CellState>]) -> (usize, usize) {
+    previous
+        .iter()
+        .flatten()
+        .zip(current.iter().flatten())
+        .fold((0, 0), |(births, deaths), (before, after)| {
+            match (before.is_alive(), after.is_alive()) {
+                (false, true) => (births + 1, deaths),
+                (true, false) => (births, deaths + 1),
+                _ => (births, deaths)
+            }
+        })
+}
+
+#[cfg(test)]
+mod statistics_tests {
+    use super::*;
+
+    #[test]
+    fn should_compute_population_bounding_box_and_density() {
+        let states = vec![
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::ALIVE],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD],
+        ];
+
+        let statistics = GenerationStatistics::from_states(4, 1, 2, &states);
+
+        assert_eq!(statistics.generation, 4);
+        assert_eq!(statistics.population, 3);
+        assert_eq!(statistics.births, 1);
+        assert_eq!(statistics.deaths, 2);
+        assert_eq!(statistics.bounding_box, Some(BoundingBox { min_x: 1, min_y: 1, max_x: 2, max_y: 2 }));
+        assert_eq!(statistics.density, 3.0 / 9.0);
+    }
+
+    #[test]
+    fn should_have_no_bounding_box_when_every_cell_is_dead() {
+        let states = vec![vec![CellState::DEAD, CellState::DEAD]];
+
+        let statistics = GenerationStatistics::from_states(0, 0, 0, &states);
+
+        assert_eq!(statistics.bounding_box, None);
+        assert_eq!(statistics.to_csv(), "0,0,0,0,,,,,0.0000");
+    }
+
+    #[test]
+    fn should_count_births_and_deaths_between_generations() {
+        let previous = vec![vec![CellState::ALIVE, CellState::DEAD, CellState::ALIVE]];
+        let current = vec![vec![CellState::DYING(1), CellState::ALIVE, CellState::ALIVE]];
+
+        assert_eq!(count_transitions(&previous, &current), (1, 1));
+    }
+}
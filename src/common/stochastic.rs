@@ -0,0 +1,160 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::common::cell_state::CellState;
+use crate::common::neighbourhood::Neighbourhood;
+use crate::common::relative_position::RelativePosition;
+use crate::common::rule::Rule;
+
+/// Noisy version of another rule: the births it calls for only happen with a
+/// given probability, and so do its survivals, cells failing to survive dying
+/// at once.
+pub struct StochasticRule {
+    rule: Rc<dyn Rule>,
+    birth_probability: f64,
+    survival_probability: f64,
+    rng: RefCell<ChaCha12Rng>,
+}
+
+impl StochasticRule {
+    pub fn new(rule: Rc<dyn Rule>, birth_probability: f64, survival_probability: f64) -> StochasticRule {
+        StochasticRule {
+            rule,
+            birth_probability: birth_probability.clamp(0.0, 1.0),
+            survival_probability: survival_probability.clamp(0.0, 1.0),
+            rng: RefCell::new(ChaCha12Rng::from_entropy()),
+        }
+    }
+
+    /// Draws the same births and survivals at every run with the same seed.
+    pub fn with_seed(self, seed: u64) -> StochasticRule {
+        StochasticRule {
+            rng: RefCell::new(ChaCha12Rng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    /// Goes on drawing from the given generator, as saved by a snapshot.
+    pub fn with_rng(self, rng: ChaCha12Rng) -> StochasticRule {
+        StochasticRule {
+            rng: RefCell::new(rng),
+            ..self
+        }
+    }
+
+    pub fn birth_probability(&self) -> f64 {
+        self.birth_probability
+    }
+
+    pub fn survival_probability(&self) -> f64 {
+        self.survival_probability
+    }
+
+    /// Copy of the generator, left where it is.
+    pub fn rng(&self) -> ChaCha12Rng {
+        self.rng.borrow().clone()
+    }
+
+    pub fn rule(&self) -> Rc<dyn Rule> {
+        Rc::clone(&self.rule)
+    }
+
+    fn happens(&self, probability: f64) -> bool {
+        self.rng.borrow_mut().gen_bool(probability)
+    }
+}
+
+impl fmt::Debug for StochasticRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StochasticRule")
+            .field("rule", &self.rule)
+            .field("birth_probability", &self.birth_probability)
+            .field("survival_probability", &self.survival_probability)
+            .finish()
+    }
+}
+
+impl Rule for StochasticRule {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        let next_state = self.rule.next_state(state, neighbours);
+        match (state.is_alive(), next_state.is_alive()) {
+            (false, true) if !self.happens(self.birth_probability) => state.clone(),
+            (true, true) if !self.happens(self.survival_probability) => CellState::DEAD,
+            _ => next_state,
+        }
+    }
+
+    /// Name of the underlying rule, the probabilities not being part of any rulestring.
+    fn name(&self) -> String {
+        self.rule.name()
+    }
+
+    fn as_stochastic(&self) -> Option<&StochasticRule> {
+        Some(self)
+    }
+
+    fn neighbourhood(&self) -> Option<Neighbourhood> {
+        self.rule.neighbourhood()
+    }
+
+    fn colours(&self) -> u8 {
+        self.rule.colours()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    fn state_of_index(&self, index: u8) -> CellState {
+        self.rule.state_of_index(index)
+    }
+}
+
+#[cfg(test)]
+mod stochastic_tests {
+    use super::*;
+    use crate::common::rule::LifeLikeRule;
+    use crate::nopointer::universe::Universe;
+
+    fn noisy_life(birth_probability: f64, survival_probability: f64) -> Rc<dyn Rule> {
+        Rc::new(StochasticRule::new(Rc::new(LifeLikeRule::conway()), birth_probability, survival_probability).with_seed(7))
+    }
+
+    #[test]
+    fn should_follow_the_rule_when_everything_is_certain() {
+        let universe = Universe::new_with_seed(12, 12, 1);
+        let noisy = Universe::new_from_cell_states(universe.states()).with_rule(noisy_life(1.0, 1.0));
+
+        assert_eq!(noisy.tick().print(), universe.tick().print());
+    }
+
+    #[test]
+    fn should_neither_give_birth_nor_keep_cells_alive_when_impossible() {
+        let state = vec![
+            "o x o",
+            "o x o",
+            "o x o"
+        ];
+        let universe = Universe::new_from_states(&state).with_rule(noisy_life(0.0, 0.0));
+
+        assert_eq!(universe.tick().print(), vec!["o o o", "o o o", "o o o"]);
+    }
+
+    #[test]
+    fn should_draw_the_same_noise_with_the_same_seed() {
+        let states = Universe::new_with_seed(16, 16, 3).states();
+        let run = || {
+            let mut universe = Universe::new_from_cell_states(states.clone()).with_rule(noisy_life(0.7, 0.9));
+            for _ in 0..5 {
+                universe = universe.tick();
+            }
+            universe.print()
+        };
+
+        assert_eq!(run(), run());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::common::cell_state::CellState;
+
+/// Number of alive cells in every rectangle starting at the top-left corner
+/// of a grid, so that the alive cells of any rectangle are counted in
+/// constant time whatever its size.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct SummedAreaTable {
+    width: usize,
+    height: usize,
+    // One extra line and column of zeros so that rectangles touching the
+    // top or left edge need no special case
+    sums: Vec<usize>,
+}
+
+impl SummedAreaTable {
+    pub fn of_alive_cells(states: &[Vec<CellState>]) -> SummedAreaTable {
+        let mut table = SummedAreaTable::default();
+        table.count(states.first().map_or(0, |line| line.len()), states.len(), |x, y| states[y][x].is_alive());
+        table
+    }
+
+    /// Counts the alive cells of a grid of the given size again, keeping the
+    /// memory of the previous counts, so that nothing is allocated from one
+    /// generation to the next of a grid that keeps its size.
+    pub fn count(&mut self, width: usize, height: usize, is_alive: impl Fn(usize, usize) -> bool) {
+        self.width = width;
+        self.height = height;
+        self.sums.clear();
+        self.sums.resize((width + 1) * (height + 1), 0);
+
+        for y in 0..height {
+            let mut line_sum = 0;
+            for x in 0..width {
+                line_sum += is_alive(x, y) as usize;
+                self.sums[(y + 1) * (width + 1) + x + 1] = self.sums[y * (width + 1) + x + 1] + line_sum;
+            }
+        }
+    }
+
+    /// Alive cells of the rectangle between both corners included, clipped to the grid.
+    pub fn alive_between(&self, (min_x, min_y): (i64, i64), (max_x, max_y): (i64, i64)) -> usize {
+        let min_x = min_x.max(0) as usize;
+        let min_y = min_y.max(0) as usize;
+        let max_x = (max_x + 1).min(self.width as i64);
+        let max_y = (max_y + 1).min(self.height as i64);
+        if max_x <= min_x as i64 || max_y <= min_y as i64 {
+            return 0;
+        }
+        let (max_x, max_y) = (max_x as usize, max_y as usize);
+
+        self.sum_at(max_x, max_y) + self.sum_at(min_x, min_y) - self.sum_at(min_x, max_y) - self.sum_at(max_x, min_y)
+    }
+
+    /// Alive cells in the square of the given range around a cell, the cell excluded.
+    pub fn alive_around(&self, x: usize, y: usize, range: usize, state: &CellState) -> usize {
+        let (x, y, range) = (x as i64, y as i64, range as i64);
+        self.alive_between((x - range, y - range), (x + range, y + range)) - state.is_alive() as usize
+    }
+
+    fn sum_at(&self, x: usize, y: usize) -> usize {
+        self.sums[y * (self.width + 1) + x]
+    }
+}
+
+#[cfg(test)]
+mod summed_area_table_tests {
+    use super::*;
+    use crate::nopointer::universe::Universe;
+
+    #[test]
+    fn should_count_alive_cells_of_rectangles() {
+        let universe = Universe::new_from_states(&vec![
+            "x o x",
+            "x x o",
+            "o x x",
+        ]);
+        let table = SummedAreaTable::of_alive_cells(&universe.states());
+
+        assert_eq!(table.alive_between((0, 0), (2, 2)), 6);
+        assert_eq!(table.alive_between((1, 1), (2, 2)), 3);
+        assert_eq!(table.alive_between((2, 0), (2, 0)), 1);
+        assert_eq!(table.alive_between((-5, -5), (0, 1)), 2);
+        assert_eq!(table.alive_between((3, 0), (5, 2)), 0);
+    }
+
+    #[test]
+    fn should_count_a_grid_of_another_size_again() {
+        let mut table = SummedAreaTable::of_alive_cells(&vec![vec![CellState::ALIVE; 4]; 4]);
+
+        table.count(2, 3, |x, y| x == y);
+
+        assert_eq!(table, SummedAreaTable::of_alive_cells(&Universe::new_from_states(&vec!["x o", "o x", "o o"]).states()));
+        assert_eq!(table.alive_between((0, 0), (3, 3)), 2);
+    }
+
+    #[test]
+    fn should_count_alive_neighbours_without_the_cell_itself() {
+        let states = Universe::new(20, 20).states();
+        let table = SummedAreaTable::of_alive_cells(&states);
+        let universe = Universe::new_from_cell_states(states.clone());
+
+        for (y, line) in states.iter().enumerate() {
+            for (x, state) in line.iter().enumerate() {
+                let alive_neighbours = universe.get_neighbours_states_of(x, y).iter().filter(|state| state.is_alive()).count();
+                assert_eq!(table.alive_around(x, y, 1, state), alive_neighbours);
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::fmt;
+
+use crate::common::cell_state::CellState;
+
+/// How the edges of a bounded grid are handled, named after Golly's
+/// bounded grids: a neighbour beyond an edge either does not exist, is a
+/// border cell of a fixed state, or is found on another edge.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum Topology {
+    /// Cells beyond the edges do not exist.
+    #[default]
+    Plane,
+    /// Cells beyond the edges are always in the given state.
+    Bordered(CellState),
+    /// Left and right edges are joined, as well as top and bottom ones.
+    Torus,
+    /// As a torus, except that crossing the top or bottom edge mirrors the column.
+    KleinBottle,
+    /// Crossing the left or right edge mirrors the line, and crossing the top
+    /// or bottom edge mirrors the column.
+    CrossSurface,
+    /// The top edge is joined to the left one and the bottom edge to the right
+    /// one, which only fits square grids.
+    Sphere,
+}
+
+/// Where a neighbour of a cell lies.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Location {
+    Cell(usize, usize),
+    Border(CellState),
+    Outside,
+}
+
+impl Topology {
+    /// Locates the cell at the given coordinates, which may lie beyond the
+    /// edges of a grid of the given size.
+    pub fn locate(&self, x: i64, y: i64, width: usize, height: usize) -> Location {
+        let (width, height) = (width as i64, height as i64);
+        let is_inside = |x: i64, y: i64| (0..width).contains(&x) && (0..height).contains(&y);
+        if is_inside(x, y) {
+            return Location::Cell(x as usize, y as usize);
+        }
+
+        let (x, y) = match self {
+            Topology::Plane => return Location::Outside,
+            Topology::Bordered(state) => return Location::Border(state.clone()),
+            Topology::Torus => (x.rem_euclid(width), y.rem_euclid(height)),
+            Topology::KleinBottle => {
+                let x = if (0..height).contains(&y) { x } else { width - 1 - x };
+                (x.rem_euclid(width), y.rem_euclid(height))
+            }
+            Topology::CrossSurface => {
+                let (x, y) = if (0..width).contains(&x) { (x, y) } else { (x.rem_euclid(width), height - 1 - y) };
+                if (0..height).contains(&y) { (x, y) } else { (width - 1 - x, y.rem_euclid(height)) }
+            }
+            Topology::Sphere => match (x, y) {
+                (x, y) if y < 0 => (-y - 1, x),
+                (x, y) if x < 0 => (y, -x - 1),
+                (x, y) if y >= height => (width - (y - height) - 1, x),
+                (x, y) => (y, height - (x - width) - 1),
+            },
+        };
+
+        // Corners of a sphere, or ranges larger than the grid, may lead beyond
+        // the edges again
+        match is_inside(x, y) {
+            true => Location::Cell(x as usize, y as usize),
+            false => Location::Outside,
+        }
+    }
+
+    /// Checks the edges of a grid of the given size can be joined, which
+    /// spheres only do for square grids.
+    pub fn check_size(&self, width: usize, height: usize) -> Result<(), String> {
+        match self {
+            Topology::Sphere if width != height => Err(format!("A sphere needs a square grid, not a {}x{} one", width, height)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Reads `plane`, `torus`, `klein`, `cross`, `sphere`, `border:alive` and `border:dead`.
+    pub fn parse(topology: &str) -> Result<Topology, String> {
+        match topology.trim().to_ascii_lowercase().as_str() {
+            "plane" => Ok(Topology::Plane),
+            "border:alive" => Ok(Topology::Bordered(CellState::ALIVE)),
+            "border:dead" => Ok(Topology::Bordered(CellState::DEAD)),
+            "torus" => Ok(Topology::Torus),
+            "klein" => Ok(Topology::KleinBottle),
+            "cross" => Ok(Topology::CrossSurface),
+            "sphere" => Ok(Topology::Sphere),
+            _ => Err(format!("Invalid topology '{}'", topology))
+        }
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Topology::Plane => write!(f, "plane"),
+            Topology::Bordered(state) if state.is_alive() => write!(f, "border:alive"),
+            Topology::Bordered(_) => write!(f, "border:dead"),
+            Topology::Torus => write!(f, "torus"),
+            Topology::KleinBottle => write!(f, "klein"),
+            Topology::CrossSurface => write!(f, "cross"),
+            Topology::Sphere => write!(f, "sphere"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod topology_tests {
+    use super::*;
+
+    #[test]
+    fn should_locate_cells_inside_the_grid_whatever_the_topology() {
+        for topology in [Topology::Plane, Topology::Torus, Topology::KleinBottle, Topology::CrossSurface, Topology::Sphere] {
+            assert_eq!(topology.locate(2, 1, 4, 3), Location::Cell(2, 1));
+        }
+    }
+
+    #[test]
+    fn should_leave_cells_beyond_the_edges_of_a_plane() {
+        assert_eq!(Topology::Plane.locate(-1, 1, 4, 3), Location::Outside);
+        assert_eq!(Topology::Bordered(CellState::ALIVE).locate(4, 1, 4, 3), Location::Border(CellState::ALIVE));
+    }
+
+    #[test]
+    fn should_wrap_around_a_torus() {
+        assert_eq!(Topology::Torus.locate(-1, 1, 4, 3), Location::Cell(3, 1));
+        assert_eq!(Topology::Torus.locate(1, 3, 4, 3), Location::Cell(1, 0));
+        assert_eq!(Topology::Torus.locate(-1, -1, 4, 3), Location::Cell(3, 2));
+    }
+
+    #[test]
+    fn should_mirror_columns_across_the_twisted_edges_of_a_klein_bottle() {
+        assert_eq!(Topology::KleinBottle.locate(-1, 1, 4, 3), Location::Cell(3, 1));
+        assert_eq!(Topology::KleinBottle.locate(0, -1, 4, 3), Location::Cell(3, 2));
+        assert_eq!(Topology::KleinBottle.locate(1, 3, 4, 3), Location::Cell(2, 0));
+    }
+
+    #[test]
+    fn should_mirror_both_coordinates_on_a_cross_surface() {
+        assert_eq!(Topology::CrossSurface.locate(-1, 0, 4, 3), Location::Cell(3, 2));
+        assert_eq!(Topology::CrossSurface.locate(0, 3, 4, 3), Location::Cell(3, 0));
+    }
+
+    #[test]
+    fn should_join_adjacent_edges_of_a_sphere() {
+        assert_eq!(Topology::Sphere.locate(2, -1, 4, 4), Location::Cell(0, 2));
+        assert_eq!(Topology::Sphere.locate(-1, 2, 4, 4), Location::Cell(2, 0));
+        assert_eq!(Topology::Sphere.locate(1, 4, 4, 4), Location::Cell(3, 1));
+        assert_eq!(Topology::Sphere.locate(4, 1, 4, 4), Location::Cell(1, 3));
+        assert_eq!(Topology::Sphere.locate(-1, -1, 4, 4), Location::Outside);
+    }
+
+    #[test]
+    fn should_only_fit_spheres_on_square_grids() {
+        assert!(Topology::Sphere.check_size(4, 4).is_ok());
+        assert!(Topology::Sphere.check_size(4, 3).is_err());
+        assert!(Topology::Torus.check_size(4, 3).is_ok());
+    }
+
+    #[test]
+    fn should_parse_and_print_topologies() {
+        for name in ["plane", "border:alive", "border:dead", "torus", "klein", "cross", "sphere"] {
+            assert_eq!(Topology::parse(name).unwrap().to_string(), name);
+        }
+        assert!(Topology::parse("cylinder").is_err());
+    }
+}
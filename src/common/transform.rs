@@ -0,0 +1,107 @@
+use std::fmt;
+
+/// One of the eight symmetries of a square, rotations being clockwise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Transform {
+    #[default]
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirror image, left and right swapped.
+    FlipHorizontal,
+    /// Upside down, top and bottom swapped.
+    FlipVertical,
+    /// Rows and columns swapped, along the top-left to bottom-right diagonal.
+    FlipDiagonal,
+    /// Along the top-right to bottom-left diagonal.
+    FlipAntiDiagonal,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::FlipDiagonal,
+        Transform::FlipAntiDiagonal,
+    ];
+
+    /// Reads the names printed by `Display`, such as `rot90` or `flip-x`.
+    pub fn parse(transform: &str) -> Result<Transform, String> {
+        Transform::ALL
+            .into_iter()
+            .find(|candidate| candidate.to_string() == transform.trim().to_ascii_lowercase())
+            .ok_or(format!("Invalid transform '{}'", transform))
+    }
+
+    pub fn swaps_sides(&self) -> bool {
+        matches!(self, Transform::Rotate90 | Transform::Rotate270 | Transform::FlipDiagonal | Transform::FlipAntiDiagonal)
+    }
+
+    /// Width and height of a `width` x `height` block once transformed.
+    pub fn size(&self, width: usize, height: usize) -> (usize, usize) {
+        match self.swaps_sides() {
+            true => (height, width),
+            false => (width, height),
+        }
+    }
+
+    /// Where the cell at (x, y) of a `width` x `height` block ends up.
+    pub fn apply(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (right, bottom) = (width - 1 - x, height - 1 - y);
+        match self {
+            Transform::Identity => (x, y),
+            Transform::Rotate90 => (bottom, x),
+            Transform::Rotate180 => (right, bottom),
+            Transform::Rotate270 => (y, right),
+            Transform::FlipHorizontal => (right, y),
+            Transform::FlipVertical => (x, bottom),
+            Transform::FlipDiagonal => (y, x),
+            Transform::FlipAntiDiagonal => (bottom, right),
+        }
+    }
+}
+
+impl fmt::Display for Transform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Transform::Identity => "identity",
+            Transform::Rotate90 => "rot90",
+            Transform::Rotate180 => "rot180",
+            Transform::Rotate270 => "rot270",
+            Transform::FlipHorizontal => "flip-x",
+            Transform::FlipVertical => "flip-y",
+            Transform::FlipDiagonal => "flip-diag",
+            Transform::FlipAntiDiagonal => "flip-antidiag",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_and_print_every_transform() {
+        for transform in Transform::ALL {
+            assert_eq!(Transform::parse(&transform.to_string()), Ok(transform));
+        }
+        assert!(Transform::parse("rot45").is_err());
+    }
+
+    #[test]
+    fn should_move_the_corners_of_a_block() {
+        // Top-right corner of a 3x2 block
+        assert_eq!(Transform::Rotate90.apply(2, 0, 3, 2), (1, 2));
+        assert_eq!(Transform::Rotate180.apply(2, 0, 3, 2), (0, 1));
+        assert_eq!(Transform::Rotate270.apply(2, 0, 3, 2), (0, 0));
+        assert_eq!(Transform::FlipDiagonal.apply(2, 0, 3, 2), (0, 2));
+        assert_eq!(Transform::FlipAntiDiagonal.apply(2, 0, 3, 2), (1, 0));
+        assert_eq!(Transform::Rotate90.size(3, 2), (2, 3));
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+
+/// Order in which the cells of a universe take their next state during a tick.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum UpdateMode {
+    /// Every cell at once, from the states of the previous generation.
+    #[default]
+    Synchronous,
+    /// One cell after the other in a new random order at every tick, each cell
+    /// seeing the states its neighbours already took.
+    RandomOrder,
+    /// Every cell at once, each one only with the given probability and
+    /// keeping its state otherwise.
+    Probabilistic(f64),
+}
+
+impl UpdateMode {
+    /// Reads `sync`, `random-order` and `probability:P` with `P` between 0 and 1.
+    pub fn parse(update_mode: &str) -> Result<UpdateMode, String> {
+        match update_mode.trim().to_ascii_lowercase().as_str() {
+            "sync" => Ok(UpdateMode::Synchronous),
+            "random-order" => Ok(UpdateMode::RandomOrder),
+            other => match other.strip_prefix("probability:").map(|probability| probability.parse::<f64>()) {
+                Some(Ok(probability)) if (0.0..=1.0).contains(&probability) => Ok(UpdateMode::Probabilistic(probability)),
+                _ => Err(format!("Invalid update mode '{}'", update_mode))
+            }
+        }
+    }
+}
+
+impl fmt::Display for UpdateMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpdateMode::Synchronous => write!(f, "sync"),
+            UpdateMode::RandomOrder => write!(f, "random-order"),
+            UpdateMode::Probabilistic(probability) => write!(f, "probability:{}", probability),
+        }
+    }
+}
+
+#[cfg(test)]
+mod update_mode_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_and_print_update_modes() {
+        for name in ["sync", "random-order", "probability:0.5", "probability:1"] {
+            assert_eq!(UpdateMode::parse(name).unwrap().to_string(), name);
+        }
+        assert!(UpdateMode::parse("probability:1.5").is_err());
+        assert!(UpdateMode::parse("probability").is_err());
+        assert!(UpdateMode::parse("block").is_err());
+    }
+}
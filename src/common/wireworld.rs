@@ -0,0 +1,137 @@
+use crate::common::cell_state::CellState;
+use crate::common::relative_position::RelativePosition;
+use crate::common::rule::Rule;
+
+// Wireworld states keep Golly's numbering (0 empty, 1 electron head,
+// 2 electron tail, 3 conductor) so that circuits read from multi-state RLE
+// files map onto them through state_of_index.
+pub const EMPTY: CellState = CellState::DEAD;
+pub const ELECTRON_HEAD: CellState = CellState::STATE(1);
+pub const ELECTRON_TAIL: CellState = CellState::STATE(2);
+pub const CONDUCTOR: CellState = CellState::STATE(3);
+
+/// Electrons (a head followed by a tail) moving along conductors: a conductor
+/// becomes a head when one or two of its neighbours are heads.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wireworld;
+
+impl Rule for Wireworld {
+    fn next_state(&self, state: &CellState, neighbours: &[(CellState, RelativePosition)]) -> CellState {
+        match state {
+            s if s == &ELECTRON_HEAD => ELECTRON_TAIL,
+            s if s == &ELECTRON_TAIL => CONDUCTOR,
+            s if s == &CONDUCTOR => {
+                let heads = neighbours.iter().filter(|(neighbour, _)| neighbour == &ELECTRON_HEAD).count();
+                match heads {
+                    1 | 2 => ELECTRON_HEAD,
+                    _ => CONDUCTOR
+                }
+            }
+            _ => EMPTY
+        }
+    }
+
+    fn name(&self) -> String {
+        String::from("Wireworld")
+    }
+
+    fn state_of_index(&self, index: u8) -> CellState {
+        match index {
+            0 => EMPTY,
+            state => CellState::STATE(state.min(3)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod wireworld_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::common::pattern::Pattern;
+    use crate::nopointer::universe as np;
+    use crate::smartpointers::universe as sp;
+
+    // Diode letting electrons through from left to right only
+    static DIODE: &str = "x = 9, y = 3, rule = Wireworld\n3.2C$BA2C.4C$3.2C!";
+    static BLOCKED_DIODE: &str = "x = 9, y = 3, rule = Wireworld\n3.2C$4C.2CAB$3.2C!";
+
+    fn circuit(rle: &str) -> Vec<Vec<CellState>> {
+        Pattern::from_rle(rle)
+            .unwrap()
+            .states()
+            .iter()
+            .map(|line| line.iter().map(|state| Wireworld.state_of_index(state.index())).collect())
+            .collect()
+    }
+
+    fn neighbours_with_heads(heads: usize) -> Vec<(CellState, RelativePosition)> {
+        [RelativePosition::North, RelativePosition::East, RelativePosition::South, RelativePosition::West]
+            .into_iter()
+            .enumerate()
+            .map(|(index, position)| (if index < heads { ELECTRON_HEAD } else { CONDUCTOR }, position))
+            .collect()
+    }
+
+    #[test]
+    fn should_move_electrons_forward() {
+        assert_eq!(Wireworld.next_state(&ELECTRON_HEAD, &neighbours_with_heads(0)), ELECTRON_TAIL);
+        assert_eq!(Wireworld.next_state(&ELECTRON_TAIL, &neighbours_with_heads(1)), CONDUCTOR);
+        assert_eq!(Wireworld.next_state(&EMPTY, &neighbours_with_heads(2)), EMPTY);
+    }
+
+    #[test]
+    fn should_turn_conductor_into_head_next_to_one_or_two_heads() {
+        assert_eq!(Wireworld.next_state(&CONDUCTOR, &neighbours_with_heads(0)), CONDUCTOR);
+        assert_eq!(Wireworld.next_state(&CONDUCTOR, &neighbours_with_heads(1)), ELECTRON_HEAD);
+        assert_eq!(Wireworld.next_state(&CONDUCTOR, &neighbours_with_heads(2)), ELECTRON_HEAD);
+        assert_eq!(Wireworld.next_state(&CONDUCTOR, &neighbours_with_heads(3)), CONDUCTOR);
+    }
+
+    #[test]
+    fn should_carry_an_electron_along_a_wire() {
+        let universe = np::Universe::new_from_states(&vec!["q p r r r"]).with_rule(Rc::new(Wireworld));
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.print(), vec!["r q p r r"]);
+        assert_eq!(new_universe.tick().print(), vec!["r r q p r"]);
+    }
+
+    #[test]
+    fn should_run_a_circuit_read_from_rle_on_both_engines() {
+        let diode = circuit(DIODE);
+        let mut np_universe = np::Universe::new_from_cell_states(diode.clone()).with_rule(Rc::new(Wireworld));
+        let sp_universe = sp::Universe::new_with_defined_states(diode).with_rule(Rc::new(Wireworld));
+
+        for _ in 0..6 {
+            np_universe = np_universe.tick();
+            sp_universe.tick();
+        }
+
+        assert_eq!(np_universe.print()[1], "r r r r o r q p r");
+        assert_eq!(sp_universe.print(), np_universe.print());
+    }
+
+    #[test]
+    fn should_stop_electrons_going_the_wrong_way_through_a_diode() {
+        let mut universe = np::Universe::new_from_cell_states(circuit(BLOCKED_DIODE)).with_rule(Rc::new(Wireworld));
+
+        for _ in 0..8 {
+            universe = universe.tick();
+        }
+
+        assert_eq!(universe.print(), vec!["o o o r r o o o o", "r r r r o r r r r", "o o o r r o o o o"]);
+    }
+
+    #[test]
+    fn should_count_every_part_of_a_circuit_in_the_population() {
+        let mut universe = np::Universe::new_from_cell_states(circuit(DIODE)).with_rule(Rc::new(Wireworld));
+
+        for _ in 0..4 {
+            universe = universe.tick();
+            let statistics = universe.statistics();
+            assert_eq!((statistics.population, statistics.births, statistics.deaths), (12, 0, 0));
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod rule;
+pub mod space_time;
+pub mod universe;
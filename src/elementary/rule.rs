@@ -0,0 +1,119 @@
+use std::fmt;
+
+/// Rule of a one-dimensional automaton with two states, where the next state
+/// of a cell depends on the cells up to `range` away on both sides.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LinearRule {
+    /// Wolfram's elementary rules 0 to 255: bit `4l + 2c + r` of the number
+    /// is the next state of a cell `c` between cells `l` and `r`.
+    Elementary(u8),
+    /// Bit `n` of the code is the next state of a cell when `n` cells of its
+    /// neighbourhood, itself included, are alive.
+    Totalistic { range: usize, code: u64 },
+}
+
+impl LinearRule {
+    /// Reads elementary rules as `W30` (or `30`) and totalistic ones as `R2,T20`.
+    pub fn parse(rulestring: &str) -> Result<LinearRule, String> {
+        let invalid = || format!("Invalid one-dimensional rule '{}'", rulestring);
+        let rulestring_upper = rulestring.trim().to_ascii_uppercase();
+
+        match rulestring_upper.split_once(',') {
+            Some((range, code)) => {
+                let range = range.strip_prefix('R').and_then(|range| range.parse::<usize>().ok()).ok_or_else(invalid)?;
+                let code = code.strip_prefix('T').and_then(|code| code.parse::<u64>().ok()).ok_or_else(invalid)?;
+                // Sums go from 0 to the 2r + 1 cells of the neighbourhood
+                let sums = 2 * range as u32 + 2;
+                match range {
+                    1..=31 if code.checked_shr(sums).unwrap_or(0) == 0 => Ok(LinearRule::Totalistic { range, code }),
+                    _ => Err(invalid())
+                }
+            }
+            None => {
+                let number = rulestring_upper.strip_prefix('W').unwrap_or(&rulestring_upper);
+                number.parse::<u8>().map(LinearRule::Elementary).map_err(|_| invalid())
+            }
+        }
+    }
+
+    pub fn range(&self) -> usize {
+        match self {
+            LinearRule::Elementary(_) => 1,
+            LinearRule::Totalistic { range, .. } => *range,
+        }
+    }
+
+    /// Next state of the cell in the middle of its neighbourhood, given from left to right.
+    pub fn next_state(&self, neighbourhood: &[bool]) -> bool {
+        match self {
+            LinearRule::Elementary(number) => {
+                let index = neighbourhood.iter().fold(0, |index, alive| index << 1 | *alive as u8);
+                number >> index & 1 == 1
+            }
+            LinearRule::Totalistic { code, .. } => {
+                let alive = neighbourhood.iter().filter(|alive| **alive).count();
+                code >> alive & 1 == 1
+            }
+        }
+    }
+}
+
+impl Default for LinearRule {
+    fn default() -> LinearRule {
+        LinearRule::Elementary(30)
+    }
+}
+
+impl fmt::Display for LinearRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinearRule::Elementary(number) => write!(f, "W{}", number),
+            LinearRule::Totalistic { range, code } => write!(f, "R{},T{}", range, code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_and_print_rules() {
+        assert_eq!(LinearRule::parse("W110").unwrap(), LinearRule::Elementary(110));
+        assert_eq!(LinearRule::parse("30").unwrap(), LinearRule::Elementary(30));
+        assert_eq!(LinearRule::parse("r2,t20").unwrap(), LinearRule::Totalistic { range: 2, code: 20 });
+        assert_eq!(LinearRule::parse("R2,T20").unwrap().to_string(), "R2,T20");
+        assert_eq!(LinearRule::Elementary(90).to_string(), "W90");
+    }
+
+    #[test]
+    fn should_reject_invalid_rules() {
+        assert!(LinearRule::parse("W256").is_err());
+        assert!(LinearRule::parse("B3/S23").is_err());
+        assert!(LinearRule::parse("R0,T1").is_err());
+        assert!(LinearRule::parse("R1,T16").is_err());
+        assert!(LinearRule::parse("R2,20").is_err());
+    }
+
+    #[test]
+    fn should_look_up_elementary_rule_bits() {
+        let rule_30 = LinearRule::Elementary(30);
+
+        assert!(!rule_30.next_state(&[true, true, true]));
+        assert!(rule_30.next_state(&[true, false, false]));
+        assert!(rule_30.next_state(&[false, false, true]));
+        assert!(!rule_30.next_state(&[false, false, false]));
+    }
+
+    #[test]
+    fn should_match_elementary_rules_that_are_totalistic() {
+        // Rule 150 turns a cell alive when one or all three cells are alive
+        let rule_150 = LinearRule::Elementary(150);
+        let totalistic = LinearRule::parse("R1,T10").unwrap();
+
+        for index in 0..8 {
+            let neighbourhood = [index & 4 != 0, index & 2 != 0, index & 1 != 0];
+            assert_eq!(rule_150.next_state(&neighbourhood), totalistic.next_state(&neighbourhood));
+        }
+    }
+}
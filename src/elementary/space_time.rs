@@ -0,0 +1,122 @@
+use std::io;
+
+use crate::common::cell_state::CellState;
+use crate::common::simulation::Simulation;
+
+/// Successive generations of a one-dimensional universe drawn as rows, time
+/// flowing downwards. Universes of several lines are drawn one generation
+/// under the other.
+#[derive(Debug, PartialEq)]
+pub struct SpaceTimeDiagram {
+    rows: Vec<Vec<CellState>>,
+}
+
+impl SpaceTimeDiagram {
+    pub fn new() -> SpaceTimeDiagram {
+        SpaceTimeDiagram { rows: vec![] }
+    }
+
+    /// Records the current generation of the simulation and the given number of following ones.
+    pub fn record_run(simulation: &mut dyn Simulation, ticks: usize) -> SpaceTimeDiagram {
+        let mut diagram = SpaceTimeDiagram::new();
+        diagram.record(&simulation.states());
+        for _ in 0..ticks {
+            simulation.advance();
+            diagram.record(&simulation.states());
+        }
+        diagram
+    }
+
+    pub fn record(&mut self, states: &[Vec<CellState>]) {
+        self.rows.extend(states.iter().cloned());
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// One line of text per generation, alive cells as `#` and dead ones as `.`.
+    pub fn print(&self) -> Vec<String> {
+        self
+            .rows
+            .iter()
+            .map(|row| row
+                .iter()
+                .map(|state| if state.is_alive() { '#' } else { '.' })
+                .collect()
+            )
+            .collect()
+    }
+
+    /// Binary greyscale image, alive cells in black on white.
+    pub fn to_pgm(&self) -> Vec<u8> {
+        let mut image = format!("P5\n{} {}\n255\n", self.width(), self.height()).into_bytes();
+        image.extend(self.pixels());
+        image
+    }
+
+    pub fn to_png(&self) -> io::Result<Vec<u8>> {
+        let mut image: Vec<u8> = vec![];
+        {
+            let mut encoder = png::Encoder::new(&mut image, self.width() as u32, self.height() as u32);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(&self.pixels())?;
+        }
+        Ok(image)
+    }
+
+    fn pixels(&self) -> Vec<u8> {
+        let width = self.width();
+        self
+            .rows
+            .iter()
+            .flat_map(|row| (0..width).map(move |x| match row.get(x) {
+                Some(state) if state.is_alive() => 0,
+                _ => 255,
+            }))
+            .collect()
+    }
+}
+
+impl Default for SpaceTimeDiagram {
+    fn default() -> SpaceTimeDiagram {
+        SpaceTimeDiagram::new()
+    }
+}
+
+#[cfg(test)]
+mod space_time_tests {
+    use super::*;
+    use crate::elementary::rule::LinearRule;
+    use crate::elementary::universe::Universe;
+
+    #[test]
+    fn should_draw_a_sierpinski_triangle_with_rule_90() {
+        let mut universe = Universe::new_with_single_cell(9).with_rule(LinearRule::Elementary(90));
+
+        let diagram = SpaceTimeDiagram::record_run(&mut universe, 3);
+
+        assert_eq!(diagram.print(), vec![
+            "....#....",
+            "...#.#...",
+            "..#...#..",
+            ".#.#.#.#.",
+        ]);
+    }
+
+    #[test]
+    fn should_write_a_pixel_per_cell_and_generation() {
+        let mut universe = Universe::new_with_single_cell(3).with_rule(LinearRule::Elementary(90));
+
+        let diagram = SpaceTimeDiagram::record_run(&mut universe, 1);
+
+        assert_eq!(diagram.to_pgm(), [b"P5\n3 2\n255\n".to_vec(), vec![255, 0, 255, 0, 255, 0]].concat());
+        assert!(diagram.to_png().unwrap().starts_with(b"\x89PNG"));
+    }
+}
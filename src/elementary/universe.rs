@@ -0,0 +1,203 @@
+use std::slice;
+
+use rand::Rng;
+
+use crate::common::cell_state::CellState;
+use crate::common::simulation::Simulation;
+use crate::common::statistics::{count_transitions, GenerationStatistics};
+use crate::common::topology::{Location, Topology};
+use crate::elementary::rule::LinearRule;
+
+/// Line of cells of a one-dimensional automaton, under Wolfram's elementary
+/// rule 30 unless told otherwise.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Universe {
+    cells: Vec<CellState>,
+    rule: LinearRule,
+    topology: Topology,
+    generation: usize,
+    births: usize,
+    deaths: usize,
+}
+
+impl Universe {
+    pub fn new(width: usize) -> Universe {
+        let cells = (0..width)
+            .map(|_| match rand::thread_rng().gen_range(0..2) {
+                0 => CellState::DEAD,
+                _ => CellState::ALIVE,
+            })
+            .collect();
+        Universe::new_from_cell_states(cells)
+    }
+
+    /// A single alive cell in the middle, the usual start of Wolfram's diagrams.
+    pub fn new_with_single_cell(width: usize) -> Universe {
+        let mut cells = vec![CellState::DEAD; width];
+        cells[width / 2] = CellState::ALIVE;
+        Universe::new_from_cell_states(cells)
+    }
+
+    pub fn new_from_states(states: &str) -> Universe {
+        Universe::new_from_cell_states(states
+            .chars()
+            .filter(|cell| cell != &' ')
+            .map(CellState::from_char)
+            .collect())
+    }
+
+    pub fn new_from_cell_states(cells: Vec<CellState>) -> Universe {
+        Universe {
+            cells,
+            rule: LinearRule::default(),
+            topology: Topology::default(),
+            generation: 0,
+            births: 0,
+            deaths: 0,
+        }
+    }
+
+    pub fn with_rule(self, rule: LinearRule) -> Universe {
+        Universe {
+            rule,
+            ..self
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Universe {
+        Universe {
+            topology,
+            ..self
+        }
+    }
+
+    pub fn tick(&self) -> Universe {
+        let range = self.rule.range() as i64;
+        let cells = (0..self.cells.len() as i64)
+            .map(|x| {
+                let neighbourhood = (x - range..=x + range).map(|x| self.is_alive_at(x)).collect::<Vec<bool>>();
+                match self.rule.next_state(&neighbourhood) {
+                    true => CellState::ALIVE,
+                    false => CellState::DEAD,
+                }
+            })
+            .collect::<Vec<CellState>>();
+        let (births, deaths) = count_transitions(slice::from_ref(&self.cells), slice::from_ref(&cells));
+
+        Universe {
+            cells,
+            rule: self.rule.clone(),
+            topology: self.topology.clone(),
+            generation: self.generation + 1,
+            births,
+            deaths,
+        }
+    }
+
+    fn is_alive_at(&self, x: i64) -> bool {
+        match self.topology.locate(x, 0, self.cells.len(), 1) {
+            Location::Cell(x, _) => self.cells[x].is_alive(),
+            Location::Border(state) => state.is_alive(),
+            Location::Outside => false,
+        }
+    }
+
+    pub fn print(&self) -> String {
+        self
+            .cells
+            .iter()
+            .map(|cell| cell.print())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    pub fn states(&self) -> Vec<CellState> {
+        self.cells.clone()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn rule(&self) -> &LinearRule {
+        &self.rule
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        GenerationStatistics::from_states(self.generation, self.births, self.deaths, &[self.states()])
+    }
+}
+
+impl Simulation for Universe {
+    fn advance(&mut self) {
+        *self = self.tick();
+    }
+
+    fn states(&self) -> Vec<Vec<CellState>> {
+        vec![Universe::states(self)]
+    }
+
+    fn statistics(&self) -> GenerationStatistics {
+        Universe::statistics(self)
+    }
+
+    fn print(&self) -> Vec<String> {
+        vec![Universe::print(self)]
+    }
+}
+
+#[cfg(test)]
+mod universe_tests {
+    use super::*;
+
+    #[test]
+    fn should_grow_rule_30_from_a_single_cell() {
+        let mut universe = Universe::new_with_single_cell(7);
+        let mut lines = vec![universe.print()];
+
+        for _ in 0..3 {
+            universe = universe.tick();
+            lines.push(universe.print());
+        }
+
+        assert_eq!(lines, vec![
+            "o o o x o o o",
+            "o o x x x o o",
+            "o x x o o x o",
+            "x x o x x x x",
+        ]);
+    }
+
+    #[test]
+    fn should_wrap_around_on_a_torus() {
+        let universe = Universe::new_from_states("x o o o o")
+            .with_rule(LinearRule::Elementary(90))
+            .with_topology(Topology::Torus);
+
+        assert_eq!(universe.tick().print(), "o x o o x");
+    }
+
+    #[test]
+    fn should_use_cells_further_away_with_a_larger_range() {
+        // Alive when exactly one cell of the five, itself included, is alive
+        let universe = Universe::new_from_states("o o o o x o o o o")
+            .with_rule(LinearRule::parse("R2,T2").unwrap());
+
+        assert_eq!(universe.tick().print(), "o o x x x x x o o");
+    }
+
+    #[test]
+    fn should_report_statistics_of_the_last_tick() {
+        let universe = Universe::new_with_single_cell(5).tick();
+
+        let statistics = universe.statistics();
+
+        assert_eq!(statistics.generation, 1);
+        assert_eq!(statistics.population, 3);
+        assert_eq!((statistics.births, statistics.deaths), (2, 0));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod nopointer;
+pub mod common;
+pub mod elementary;
+pub mod margolus;
+pub mod smartpointers;
@@ -1,51 +1,303 @@
-use std::{thread, time};
-use crate::smartpointers::universe as sp;
-use crate::nopointer::universe as np;
+use std::fs::File;
+use std::io::{self, Write};
+use std::{env, process, thread, time};
+use std::rc::Rc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use kata_game_of_life_rs::common::age_map::AgeMap;
+use kata_game_of_life_rs::common::catalogue;
+use kata_game_of_life_rs::common::cell_state::CellState;
+use kata_game_of_life_rs::common::differential::DifferentialRun;
+use kata_game_of_life_rs::common::heat_map::HeatMap;
+use kata_game_of_life_rs::common::pattern::Pattern;
+use kata_game_of_life_rs::common::rule::{self, LifeLikeRule, Rule};
+use kata_game_of_life_rs::common::simulation::Simulation;
+use kata_game_of_life_rs::common::statistics::GenerationStatistics;
+use kata_game_of_life_rs::common::stochastic::StochasticRule;
+use kata_game_of_life_rs::elementary::rule::LinearRule;
+use kata_game_of_life_rs::elementary::space_time::SpaceTimeDiagram;
+use kata_game_of_life_rs::elementary::universe as linear;
+use kata_game_of_life_rs::margolus::rule::BlockRule;
+use kata_game_of_life_rs::margolus::universe as margolus;
+use kata_game_of_life_rs::smartpointers::universe as sp;
+use kata_game_of_life_rs::nopointer::universe as np;
+use crate::cli::{Engine, Options};
 
-mod nopointer;
-mod common;
-mod smartpointers;
+mod cli;
 
-fn print_universe(universe: &sp::Universe) {
-    for line_to_print in universe.print() {
-        println!("{:?}", line_to_print);
+fn print_simulation(simulation: &dyn Simulation) {
+    match simulation.ages() {
+        Some(ages) => {
+            for line_to_print in AgeMap::new(ages).print() {
+                println!("{}", line_to_print);
+            }
+        }
+        None => {
+            for line_to_print in simulation.print() {
+                println!("{:?}", line_to_print);
+            }
+        }
     }
 }
 
-fn print_universe_np(universe: &np::Universe) {
-    for line_to_print in universe.print() {
-        println!("{:?}", line_to_print);
+fn load_pattern(options: &Options) -> Result<Option<Pattern>, String> {
+    match &options.pattern {
+        None => Ok(None),
+        Some(name) if catalogue::find(name).is_some() => Ok(Some(catalogue::pattern(name)?)),
+        Some(path) => Ok(Some(Pattern::from_rle_file(path)?)),
     }
 }
 
-fn main() {
-    println!("With smart pointers");
-    let universe = sp::Universe::new(10, 10);
-    let number_of_ticks = 10;
-    let sleep_duration = time::Duration::from_millis(500);
+fn rule_of(options: &Options, pattern: &Option<Pattern>) -> Result<Rc<dyn Rule>, String> {
+    let rule: Rc<dyn Rule> = match options.rule.as_deref().or(pattern.as_ref().and_then(|pattern| pattern.rule())) {
+        Some(name) => rule::from_name(name)?,
+        None => Rc::new(LifeLikeRule::conway()),
+    };
+    if options.birth_probability == 1.0 && options.survival_probability == 1.0 {
+        return Ok(rule);
+    }
+
+    let noisy_rule = StochasticRule::new(rule, options.birth_probability, options.survival_probability);
+    Ok(Rc::new(match options.seed {
+        Some(seed) => noisy_rule.with_seed(seed),
+        None => noisy_rule,
+    }))
+}
+
+fn new_linear_universe(options: &Options, pattern: Option<Pattern>) -> Result<linear::Universe, String> {
+    let rule = match options.rule.as_deref().or(pattern.as_ref().and_then(|pattern| pattern.rule())) {
+        Some(name) => LinearRule::parse(name)?,
+        None => LinearRule::default(),
+    };
+    let universe = match pattern {
+        Some(pattern) => linear::Universe::new_from_cell_states(pattern.centered_in(options.width, 1).swap_remove(0)),
+        None => linear::Universe::new(options.width),
+    };
+
+    Ok(universe.with_rule(rule).with_topology(options.topology.clone()))
+}
+
+fn new_block_universe(options: &Options, pattern: Option<Pattern>) -> Result<margolus::Universe, String> {
+    let rule = match options.rule.as_deref().or(pattern.as_ref().and_then(|pattern| pattern.rule())) {
+        Some(name) => BlockRule::parse(name)?,
+        None => BlockRule::default(),
+    };
+    let universe = match (pattern, options.seed) {
+        (Some(pattern), _) => margolus::Universe::new_from_cell_states(pattern.centered_in(options.width, options.height)),
+        (None, Some(seed)) => margolus::Universe::new_with_seed(options.width, options.height, seed),
+        (None, None) => margolus::Universe::new(options.width, options.height),
+    };
+
+    Ok(universe.with_rule(rule).with_topology(options.topology.clone()))
+}
+
+fn random_coloured_states(options: &Options, colours: u8, seed: u64) -> Vec<Vec<CellState>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..options.height)
+        .map(|_| (0..options.width)
+            .map(|_| match rng.gen_bool(0.5) {
+                true => CellState::of_colour(rng.gen_range(1..=colours)),
+                false => CellState::DEAD,
+            })
+            .collect()
+        )
+        .collect()
+}
+
+fn new_simulation(options: &Options) -> Result<Box<dyn Simulation>, String> {
+    let pattern = load_pattern(options)?;
+    if options.engine == Engine::Elementary {
+        return Ok(Box::new(new_linear_universe(options, pattern)?));
+    }
+    if options.engine == Engine::Margolus {
+        return Ok(Box::new(new_block_universe(options, pattern)?));
+    }
+    let rule = rule_of(options, &pattern)?;
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let states: Option<Vec<Vec<CellState>>> = match pattern {
+        Some(pattern) => Some(pattern
+            .centered_in(options.width, options.height)
+            .iter()
+            .map(|line| line.iter().map(|state| rule.state_of_index(state.index())).collect())
+            .collect()),
+        None if rule.colours() > 1 => Some(random_coloured_states(options, rule.colours(), seed)),
+        None => None,
+    };
+
+    let neighbourhood = rule.neighbourhood().unwrap_or_else(|| options.neighbourhood.clone());
+    let topology = options.topology.clone();
+
+    let update_mode = options.update_mode.clone();
+
+    Ok(match (&options.engine, states) {
+        (Engine::SmartPointers, states) => {
+            let universe = match states {
+                Some(states) => sp::Universe::new_with_defined_states(states).with_seed(seed),
+                None => sp::Universe::new_with_seed(options.width, options.height, seed),
+            };
+            let universe = universe.with_rule(rule).with_neighbourhood(neighbourhood).with_topology(topology).with_update_mode(update_mode);
+            Box::new(if options.show_ages { universe.with_age_tracking() } else { universe })
+        }
+        (_, states) => {
+            let universe = match states {
+                Some(states) => np::Universe::new_from_cell_states(states).with_seed(seed),
+                None => np::Universe::new_with_seed(options.width, options.height, seed),
+            };
+            let universe = universe.with_rule(rule).with_neighbourhood(neighbourhood).with_topology(topology).with_update_mode(update_mode);
+            let universe = if options.second_order { universe.with_second_order() } else { universe };
+            Box::new(if options.show_ages { universe.with_age_tracking() } else { universe })
+        }
+    })
+}
+
+fn dump_statistics(options: &Options, path: &str) -> io::Result<()> {
+    let mut simulation = new_simulation(options).map_err(io::Error::other)?;
+    let mut output: Box<dyn Write> = match path {
+        "-" => Box::new(io::stdout()),
+        _ => Box::new(File::create(path)?),
+    };
+
+    writeln!(output, "{}", GenerationStatistics::csv_header())?;
+    writeln!(output, "{}", simulation.statistics().to_csv())?;
+    for _ in 0..options.ticks {
+        simulation.advance();
+        writeln!(output, "{}", simulation.statistics().to_csv())?;
+    }
+
+    output.flush()
+}
 
-    print_universe(&universe);
-    for round in 0..=number_of_ticks {
-        thread::sleep(sleep_duration);
-        println!("Tick {}", round);
-        universe.tick();
-        print_universe(&universe);
-        println!("\n");
-        print!("{}[2J", 27 as char);
+fn draw_heat_map(options: &Options, path: &str) -> io::Result<()> {
+    let mut simulation = new_simulation(options).map_err(io::Error::other)?;
+    let heat_map = HeatMap::record_run(simulation.as_mut(), options.ticks);
+
+    match path {
+        "-" => {
+            for line_to_print in heat_map.print() {
+                println!("{}", line_to_print);
+            }
+            Ok(())
+        }
+        _ if path.ends_with(".pgm") => std::fs::write(path, heat_map.to_pgm()),
+        _ => std::fs::write(path, heat_map.to_png()?),
     }
+}
+
+fn draw_space_time(options: &Options, path: &str) -> io::Result<()> {
+    let mut simulation = new_simulation(options).map_err(io::Error::other)?;
+    let diagram = SpaceTimeDiagram::record_run(simulation.as_mut(), options.ticks);
+
+    match path {
+        "-" => {
+            for line_to_print in diagram.print() {
+                println!("{}", line_to_print);
+            }
+            Ok(())
+        }
+        _ if path.ends_with(".pgm") => std::fs::write(path, diagram.to_pgm()),
+        _ => std::fs::write(path, diagram.to_png()?),
+    }
+}
+
+// Both engines start from the same seed, drawn once when none is given
+fn verify(options: &Options) -> Result<(), String> {
+    let options = Options { seed: Some(options.seed.unwrap_or_else(rand::random)), ..options.clone() };
+    let divergence = DifferentialRun::new()
+        .with_engine("sp", new_simulation(&Options { engine: Engine::SmartPointers, ..options.clone() })?)
+        .with_engine("np", new_simulation(&Options { engine: Engine::NoPointer, ..options.clone() })?)
+        .run(options.ticks);
+
+    match divergence {
+        Some(divergence) => Err(format!("{} (seed {})", divergence, options.seed.unwrap_or_default())),
+        None => {
+            println!("sp and np agree over {} generations (seed {})", options.ticks, options.seed.unwrap_or_default());
+            Ok(())
+        }
+    }
+}
+
+fn list_patterns() {
+    for entry in catalogue::entries() {
+        let period = entry.period.map_or(String::from("-"), |period| period.to_string());
+        let lifespan = entry.lifespan.map_or(String::new(), |lifespan| format!(", settles after {} generations", lifespan));
+        println!("{} ({}, period {}, displacement {:?}, rule {}{})", entry.name, entry.kind, period, entry.displacement, entry.rule(), lifespan);
+    }
+}
 
-    println!("It started with lifetimes but finally no pointer as to force SRP");
-    let mut universe = np::Universe::new(10, 10);
-    let number_of_ticks = 10;
+fn run_demo(options: &Options) -> Result<(), String> {
+    let engines = match options.engine {
+        Engine::Elementary => vec![(Engine::Elementary, "One-dimensional")],
+        Engine::Margolus => vec![(Engine::Margolus, "Margolus neighbourhood")],
+        _ if options.second_order => vec![(Engine::NoPointer, "Second-order, reversible")],
+        _ => vec![
+            (Engine::SmartPointers, "With smart pointers"),
+            (Engine::NoPointer, "It started with lifetimes but finally no pointer as to force SRP"),
+        ],
+    };
     let sleep_duration = time::Duration::from_millis(500);
 
-    print_universe_np(&universe);
-    for round in 0..=number_of_ticks {
-        thread::sleep(sleep_duration);
-        let new_universe = universe.tick();
-        println!("Tick {}", round);
-        print_universe_np(&universe);
-        universe = new_universe;
-        println!("\n");
+    for (engine, title) in engines {
+        println!("{}", title);
+        let mut simulation = new_simulation(&Options { engine, ..options.clone() })?;
+
+        print_simulation(simulation.as_ref());
+        for round in 0..=options.ticks {
+            thread::sleep(sleep_duration);
+            println!("Tick {}", round);
+            simulation.advance();
+            print_simulation(simulation.as_ref());
+            println!("\n");
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!("{}", Options::usage());
+            process::exit(2);
+        }
+    };
+
+    if options.list_patterns {
+        list_patterns();
+        return;
+    }
+    if options.verify {
+        if let Err(error) = verify(&options) {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+        return;
+    }
+    // Every file is drawn from the same universe, its seed drawn once when none is given
+    let options = Options { seed: Some(options.seed.unwrap_or_else(rand::random)), ..options };
+    if let Some(path) = &options.stats_csv {
+        if let Err(error) = dump_statistics(&options, path) {
+            eprintln!("Could not write statistics to {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+    if let Some(path) = &options.heat_map {
+        if let Err(error) = draw_heat_map(&options, path) {
+            eprintln!("Could not write heat map to {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+    if let Some(path) = &options.space_time {
+        if let Err(error) = draw_space_time(&options, path) {
+            eprintln!("Could not write space-time diagram to {}: {}", path, error);
+            process::exit(1);
+        }
+    }
+    if options.stats_csv.is_none() && options.heat_map.is_none() && options.space_time.is_none() {
+        if let Err(error) = run_demo(&options) {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
     }
 }
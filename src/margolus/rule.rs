@@ -0,0 +1,112 @@
+use std::fmt;
+
+/// Rule of a block automaton: every 2x2 block of cells is replaced at once by
+/// the block found in a 16-entry table. Blocks are numbered as in Golly and
+/// MCell, adding 1 for an alive top-left cell, 2 for the top-right one, 4 for
+/// the bottom-left one and 8 for the bottom-right one.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BlockRule {
+    name: Option<String>,
+    table: [u8; 16],
+}
+
+impl BlockRule {
+    pub fn new(table: [u8; 16]) -> BlockRule {
+        BlockRule { name: None, table }
+    }
+
+    /// Fredkin's billiard ball model, balls bouncing off each other and off walls.
+    pub fn billiard_ball_model() -> BlockRule {
+        BlockRule::named("BBM", [0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15])
+    }
+
+    pub fn critters() -> BlockRule {
+        BlockRule::named("Critters", [15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0])
+    }
+
+    pub fn tron() -> BlockRule {
+        BlockRule::named("Tron", [15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0])
+    }
+
+    /// Reads built-in rules by name (`BBM`, `Critters`, `Tron`) or tables
+    /// written as Golly does, such as `M0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,15`.
+    pub fn parse(rulestring: &str) -> Result<BlockRule, String> {
+        let invalid = || format!("Invalid block rule '{}'", rulestring);
+
+        match rulestring.trim().to_ascii_lowercase().as_str() {
+            "bbm" => Ok(BlockRule::billiard_ball_model()),
+            "critters" => Ok(BlockRule::critters()),
+            "tron" => Ok(BlockRule::tron()),
+            table => {
+                let entries = table
+                    .strip_prefix('m')
+                    .ok_or_else(invalid)?
+                    .split(',')
+                    .map(|entry| entry.trim().parse::<u8>().ok().filter(|entry| *entry < 16).ok_or_else(invalid))
+                    .collect::<Result<Vec<u8>, String>>()?;
+                let table = <[u8; 16]>::try_from(entries).map_err(|_| invalid())?;
+                Ok(BlockRule::new(table))
+            }
+        }
+    }
+
+    /// Block replacing the given one, the cells being given as top-left, top-right, bottom-left and bottom-right.
+    pub fn next_block(&self, block: [bool; 4]) -> [bool; 4] {
+        let index = block.iter().enumerate().fold(0, |index, (bit, alive)| index | (*alive as usize) << bit);
+        let next = self.table[index];
+        [next & 1 != 0, next & 2 != 0, next & 4 != 0, next & 8 != 0]
+    }
+
+    fn named(name: &str, table: [u8; 16]) -> BlockRule {
+        BlockRule { name: Some(String::from(name)), table }
+    }
+}
+
+impl Default for BlockRule {
+    fn default() -> BlockRule {
+        BlockRule::billiard_ball_model()
+    }
+}
+
+impl fmt::Display for BlockRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "M{}", self.table.iter().map(|entry| entry.to_string()).collect::<Vec<String>>().join(",")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod rule_tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_built_in_rules_and_tables() {
+        assert_eq!(BlockRule::parse("critters").unwrap(), BlockRule::critters());
+        assert_eq!(BlockRule::parse("M0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,15").unwrap().next_block([true, false, false, false]), [false, false, false, true]);
+        assert_eq!(BlockRule::parse("M15,1,2,3,4,5,6,7,8,9,10,11,12,13,14,0").unwrap().to_string(), "M15,1,2,3,4,5,6,7,8,9,10,11,12,13,14,0");
+        assert_eq!(BlockRule::tron().to_string(), "Tron");
+    }
+
+    #[test]
+    fn should_reject_invalid_tables() {
+        assert!(BlockRule::parse("M0,1,2").is_err());
+        assert!(BlockRule::parse("M0,8,4,3,2,5,9,7,1,6,10,11,12,13,14,16").is_err());
+        assert!(BlockRule::parse("B3/S23").is_err());
+    }
+
+    #[test]
+    fn should_send_a_lone_ball_across_its_block() {
+        let bbm = BlockRule::billiard_ball_model();
+
+        assert_eq!(bbm.next_block([false, true, false, false]), [false, false, true, false]);
+        assert_eq!(bbm.next_block([false, false, false, true]), [true, false, false, false]);
+    }
+
+    #[test]
+    fn should_invert_empty_and_full_blocks_with_tron() {
+        assert_eq!(BlockRule::tron().next_block([false; 4]), [true; 4]);
+        assert_eq!(BlockRule::tron().next_block([true; 4]), [false; 4]);
+    }
+}
@@ -0,0 +1,250 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::common::cell_state::CellState;
+use crate::common::simulation::Simulation;
+use crate::common::statistics::{count_transitions, GenerationStatistics};
+use crate::common::topology::{Location, Topology};
+use crate::margolus::rule::BlockRule;
+
+// Cells of a block, as offsets from its top-left cell in the order of BlockRule::next_block
+static BLOCK_OFFSETS: [(i64, i64); 4] = [(0, 0), (1, 0), (0, 1), (1, 1)];
+
+/// Grid under a block rule with the Margolus neighbourhood: the grid is
+/// partitioned into 2x2 blocks, the partition moving one cell down and
+/// right at every other generation. On wrapped topologies blocks only tile
+/// the grid exactly when its sides are even, otherwise the last block of a
+/// line overlaps the first one and wins over it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Universe {
+    width: usize,
+    height: usize,
+    states: Vec<Vec<CellState>>,
+    rule: BlockRule,
+    topology: Topology,
+    generation: usize,
+    births: usize,
+    deaths: usize,
+}
+
+impl Universe {
+    pub fn new(width: usize, height: usize) -> Universe {
+        Self::new_random(width, height, &mut rand::thread_rng())
+    }
+
+    pub fn new_with_seed(width: usize, height: usize, seed: u64) -> Universe {
+        Self::new_random(width, height, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn new_from_states(states: &Vec<&str>) -> Universe {
+        Self::new_from_cell_states(states
+            .iter()
+            .map(|line| line
+                .chars()
+                .filter(|cell| cell != &' ')
+                .map(CellState::from_char)
+                .collect()
+            )
+            .collect())
+    }
+
+    pub fn new_from_cell_states(states: Vec<Vec<CellState>>) -> Universe {
+        Universe {
+            width: states[0].len(),
+            height: states.len(),
+            states,
+            rule: BlockRule::default(),
+            topology: Topology::default(),
+            generation: 0,
+            births: 0,
+            deaths: 0,
+        }
+    }
+
+    pub fn with_rule(self, rule: BlockRule) -> Universe {
+        Universe {
+            rule,
+            ..self
+        }
+    }
+
+    pub fn with_topology(self, topology: Topology) -> Universe {
+        Universe {
+            topology,
+            ..self
+        }
+    }
+
+    pub fn tick(&self) -> Universe {
+        // Blocks start on even coordinates at even generations, on odd ones otherwise
+        let phase = (self.generation % 2) as i64;
+        let wraps_horizontally = matches!(self.topology.locate(-1, 0, self.width, self.height), Location::Cell(_, _));
+        let wraps_vertically = matches!(self.topology.locate(0, -1, self.width, self.height), Location::Cell(_, _));
+        let mut states = self.states.clone();
+
+        for origin_y in Self::block_origins(phase, self.height, wraps_vertically) {
+            for origin_x in Self::block_origins(phase, self.width, wraps_horizontally) {
+                let locations = BLOCK_OFFSETS.map(|(x, y)| self.topology.locate(origin_x + x, origin_y + y, self.width, self.height));
+                let block = locations.each_ref().map(|location| self.is_alive_at(location));
+
+                for (location, alive) in locations.iter().zip(self.rule.next_block(block)) {
+                    if let Location::Cell(x, y) = location {
+                        states[*y][*x] = if alive { CellState::ALIVE } else { CellState::DEAD };
+                    }
+                }
+            }
+        }
+        let (births, deaths) = count_transitions(&self.states, &states);
+
+        Universe {
+            states,
+            rule: self.rule.clone(),
+            topology: self.topology.clone(),
+            generation: self.generation + 1,
+            births,
+            deaths,
+            ..*self
+        }
+    }
+
+    // First coordinate of every block along a side, blocks hanging over the
+    // edge when the partition is shifted unless the grid wraps around
+    fn block_origins(phase: i64, length: usize, wraps: bool) -> impl Iterator<Item=i64> {
+        let (start, end) = match wraps {
+            true => (phase, length as i64 + phase),
+            false => (-phase, length as i64),
+        };
+        (start..end).step_by(2)
+    }
+
+    fn is_alive_at(&self, location: &Location) -> bool {
+        match location {
+            Location::Cell(x, y) => self.states[*y][*x].is_alive(),
+            Location::Border(state) => state.is_alive(),
+            Location::Outside => false,
+        }
+    }
+
+    pub fn print(&self) -> Vec<String> {
+        self
+            .states
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|state| state.print())
+                .collect::<Vec<String>>()
+                .join(" ")
+            )
+            .collect()
+    }
+
+    pub fn states(&self) -> Vec<Vec<CellState>> {
+        self.states.clone()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn rule(&self) -> &BlockRule {
+        &self.rule
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        GenerationStatistics::from_states(self.generation, self.births, self.deaths, &self.states)
+    }
+
+    fn new_random(width: usize, height: usize, rng: &mut impl Rng) -> Universe {
+        Self::new_from_cell_states((0..height)
+            .map(|_| (0..width)
+                .map(|_| match rng.gen_range(0..2) {
+                    0 => CellState::DEAD,
+                    _ => CellState::ALIVE,
+                })
+                .collect()
+            )
+            .collect())
+    }
+}
+
+impl Simulation for Universe {
+    fn advance(&mut self) {
+        *self = self.tick();
+    }
+
+    fn states(&self) -> Vec<Vec<CellState>> {
+        Universe::states(self)
+    }
+
+    fn statistics(&self) -> GenerationStatistics {
+        Universe::statistics(self)
+    }
+
+    fn print(&self) -> Vec<String> {
+        Universe::print(self)
+    }
+}
+
+#[cfg(test)]
+mod universe_tests {
+    use super::*;
+
+    #[test]
+    fn should_move_a_ball_diagonally_as_partitions_alternate() {
+        let universe = Universe::new_from_states(&vec![
+            "x o o o",
+            "o o o o",
+            "o o o o",
+            "o o o o",
+        ]);
+
+        let new_universe = universe.tick().tick();
+
+        assert_eq!(new_universe.print(), vec!["o o o o", "o o o o", "o o x o", "o o o o"]);
+        assert_eq!(new_universe.statistics().population, 1);
+    }
+
+    #[test]
+    fn should_bring_a_ball_back_around_a_torus() {
+        let state = vec![
+            "x o o o",
+            "o o o o",
+            "o o o o",
+            "o o o o",
+        ];
+        let mut universe = Universe::new_from_states(&state).with_topology(Topology::Torus);
+
+        for _ in 0..4 {
+            universe = universe.tick();
+        }
+
+        assert_eq!(universe.print(), Universe::new_from_states(&state).print());
+    }
+
+    #[test]
+    fn should_lose_a_ball_leaving_a_plane() {
+        let universe = Universe::new_from_states(&vec![
+            "o o o",
+            "o o o",
+            "o o x",
+        ]).tick();
+
+        assert_eq!(universe.statistics().population, 0);
+        assert_eq!(universe.statistics().deaths, 1);
+    }
+
+    #[test]
+    fn should_update_the_whole_grid_at_once_with_tron() {
+        let universe = Universe::new_from_states(&vec!["o o o o", "o o o o"]).with_rule(BlockRule::tron());
+
+        assert_eq!(universe.tick().print(), vec!["x x x x", "x x x x"]);
+    }
+}
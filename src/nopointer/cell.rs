@@ -1,40 +1,66 @@
+use crate::common::cell_age::CellAge;
 use crate::common::cell_state::CellState;
 use rand::Rng;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Cell {
-    state: CellState
+    state: CellState,
+    age: Option<CellAge>,
 }
 
 impl Cell {
     pub fn is_alive(&self) -> bool {
-        return match self.state {
-            CellState::ALIVE => { true }
-            CellState::DEAD => { false }
-        };
+        self.state.is_alive()
     }
 
     pub fn get_state(&self) -> CellState {
-        return match self.state {
-            CellState::ALIVE => { CellState::ALIVE }
-            CellState::DEAD => { CellState::DEAD }
-        };
+        self.state.clone()
     }
 
     pub fn print(&self) -> String {
-        let print = match self.is_alive() {
-            true => { "x".to_string() }
-            false => { "o".to_string() }
-        };
-        format!("{}", print)
+        self.state.print()
+    }
+
+    /// Age of the cell, when tracked.
+    pub fn age(&self) -> Option<CellAge> {
+        self.age
+    }
+
+    pub fn with_age_tracking(self) -> Cell {
+        Cell {
+            age: Some(CellAge::of(&self.state)),
+            ..self
+        }
+    }
+
+    /// The same cell, tracked from the given age on.
+    pub fn with_age(self, age: CellAge) -> Cell {
+        Cell {
+            age: Some(age),
+            ..self
+        }
+    }
+
+    /// The same cell put in another state, its age restarting from there.
+    pub fn with_state(&self, state: &CellState) -> Cell {
+        Cell {
+            state: state.clone(),
+            age: self.age.map(|_| CellAge::of(state)),
+        }
+    }
+
+    /// The cell one generation later, in the given state and older if its age is tracked.
+    pub fn next_generation(&self, state: &CellState) -> Cell {
+        Cell {
+            state: state.clone(),
+            age: self.age.map(|age| age.next(state)),
+        }
     }
 
     pub fn new(state: &CellState) -> Cell {
         Cell {
-            state: match state {
-                CellState::ALIVE => CellState::ALIVE,
-                CellState::DEAD => CellState::DEAD
-            }
+            state: state.clone(),
+            age: None,
         }
     }
 
@@ -49,12 +75,14 @@ impl Cell {
     pub fn new_alive() -> Cell {
         Cell {
             state: CellState::ALIVE,
+            age: None,
         }
     }
 
     pub fn new_dead() -> Cell {
         Cell {
             state: CellState::DEAD,
+            age: None,
         }
     }
 }
@@ -69,7 +97,7 @@ mod cell_tests {
 
         let is_alive = cell.is_alive();
 
-        assert_eq!(is_alive, true);
+        assert!(is_alive);
     }
 
     #[test]
@@ -78,6 +106,16 @@ mod cell_tests {
 
         let is_alive = cell.is_alive();
 
-        assert_eq!(is_alive, false);
+        assert!(!is_alive);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_grow_older_only_when_tracking_age() {
+        let cell = Cell::new_alive();
+        let tracked_cell = Cell::new_alive().with_age_tracking();
+
+        assert_eq!(cell.next_generation(&CellState::ALIVE).age(), None);
+        assert_eq!(tracked_cell.next_generation(&CellState::ALIVE).age(), Some(CellAge::Alive(2)));
+        assert_eq!(tracked_cell.next_generation(&CellState::DEAD).age(), Some(CellAge::Dead(1)));
+    }
+}
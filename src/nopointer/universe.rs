@@ -1,183 +1,504 @@
-use rand::Rng;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::common::active_region::ActiveRegion;
+use crate::common::cell_age::CellAge;
 use crate::common::cell_state::CellState;
+use crate::common::history::{GenerationDelta, History};
+use crate::common::neighbourhood::Neighbourhood;
 use crate::common::relative_position::RelativePosition;
+use crate::common::resize::{self, Anchor};
+use crate::common::rule::{LifeLikeRule, Rule};
+use crate::common::simulation::Simulation;
+use crate::common::snapshot::{Noise, RngState, Snapshot};
+use crate::common::stamp::Stamp;
+use crate::common::statistics::{count_transitions, GenerationStatistics};
+use crate::common::summed_area_table::SummedAreaTable;
+use crate::common::topology::{Location, Topology};
+use crate::common::update_mode::UpdateMode;
 use crate::nopointer::cell::Cell;
 
 static UNIVERSE_START_INDEX: usize = 0;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct CellPosition {
     x: usize,
     y: usize,
     cell: Cell,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Universe {
     width: usize,
     height: usize,
     cells: Vec<Vec<CellPosition>>,
+    // Grid the next generation is written to, swapped with the current one at every step
+    next_cells: Vec<Vec<CellPosition>>,
+    // Alive cells counted again at every step, in memory kept from one step to the next
+    alive_counts: SummedAreaTable,
+    rule: Rc<dyn Rule>,
+    neighbourhood: Neighbourhood,
+    topology: Topology,
+    update_mode: UpdateMode,
+    rng: RefCell<ChaCha12Rng>,
+    previous_states: Option<Vec<Vec<CellState>>>,
+    // Tiles to recompute at the next step, built at the first step that can skip any
+    active_region: Option<ActiveRegion>,
+    // Cells added beyond an edge alive cells come close to, before every step
+    expansion: Option<usize>,
+    history: History,
+    generation: usize,
+    births: usize,
+    deaths: usize,
 }
 
 impl Universe {
     /*
         INSTANCE
      */
+    /// Next generation as a new universe, leaving this one as it is.
     pub fn tick(&self) -> Universe {
-        let mut new_cells: Vec<Vec<CellPosition>> = vec![];
+        let mut universe = self.clone();
+        universe.step();
+        universe
+    }
 
-        for y in UNIVERSE_START_INDEX..self.height {
-            let mut line: Vec<CellPosition> = vec![];
-            for x in UNIVERSE_START_INDEX..self.width {
-                let cell = match self.cells.get(y) {
-                    Some(line_of_cells) => {
-                        match line_of_cells.get(x) {
-                            None => Cell::new_random_state(),
-                            Some(cell_position) => {
-                                let neighbours_states = self.get_neighbours_states_of(x, y);
-                                Cell::new(&self.next_state_of(cell_position.cell.get_state(), neighbours_states))
-                            }
+    /// Moves on to the next generation in place. Cells are written to a
+    /// second grid kept from one step to the next and swapped with the
+    /// current one, instead of a new grid being built every generation.
+    pub fn step(&mut self) {
+        self.expand_towards_alive_cells();
+        let states_before_step = match self.history.is_recording() {
+            true => Some(self.states()),
+            false => None,
+        };
+        let (generation, births, deaths) = (self.generation, self.births, self.deaths);
+
+        match (self.previous_states.take(), &self.update_mode) {
+            (Some(previous_states), _) => self.step_second_order(&previous_states),
+            (None, UpdateMode::RandomOrder) => self.step_in_random_order(),
+            (None, _) => self.step_at_once(),
+        }
+        self.generation += 1;
+
+        if let Some(states) = states_before_step {
+            self.history.record(GenerationDelta::between(generation, births, deaths, &states, &self.states()));
+        }
+    }
+
+    /// Universe as it was one tick ago, taken from the history.
+    pub fn step_back(&self) -> Result<Universe, String> {
+        let mut history = self.history.clone();
+        let delta = history.undo().ok_or("No generation left in the history to step back to")?;
+        let mut states = self.states();
+        delta.restore(&mut states);
+
+        let universe = Universe {
+            cells: self.cells_in_states(&states),
+            history,
+            generation: delta.generation,
+            births: delta.births,
+            deaths: delta.deaths,
+            ..self.next_generation(vec![], 0, 0)
+        };
+        // Second-order universes remember the generation before the restored one as well
+        let previous_states = self.previous_states.as_ref().map(|_| Self::exclusive_or(&universe.rule_output(), &self.states()));
+        Ok(Universe {
+            previous_states,
+            ..universe
+        })
+    }
+
+    /// Universe at the given generation, ticking forward or stepping back through the history.
+    pub fn goto(&self, generation: usize) -> Result<Universe, String> {
+        if generation < self.generation && self.history.earliest_generation().is_none_or(|earliest| generation < earliest) {
+            return Err(format!("Generation {} is no longer in the history", generation));
+        }
+
+        let mut universe = self.clone();
+        while universe.generation > generation {
+            universe = universe.step_back()?;
+        }
+        while universe.generation < generation {
+            universe.step();
+        }
+        Ok(universe)
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Generation before the current one, which only second-order universes remember.
+    pub fn tick_back(&self) -> Result<Universe, String> {
+        let previous_states = self.previous_states.as_ref().ok_or("Only second-order universes can step backwards")?;
+        let earlier = Universe {
+            cells: self.cells_in_states(previous_states),
+            ..self.next_generation(self.cells.clone(), 0, 0)
+        };
+        // The rule run forward from the previous generation gave the current one XOR the earlier one
+        let earlier_states = Self::exclusive_or(&earlier.rule_output(), &self.states());
+
+        let (births, deaths) = count_transitions(&earlier_states, previous_states);
+        let mut history = earlier.history.clone();
+        history.undo();
+        Ok(Universe {
+            previous_states: Some(earlier_states),
+            history,
+            generation: self.generation.saturating_sub(1),
+            births,
+            deaths,
+            ..earlier
+        })
+    }
+
+    // Every cell is alive when either the rule or the previous generation says
+    // so but not both, which lets the previous generation be found back
+    fn step_second_order(&mut self, previous_states: &[Vec<CellState>]) {
+        let states = Self::exclusive_or(&self.rule_output(), previous_states);
+        let current_states = self.states();
+
+        for (line, line_states) in self.cells.iter_mut().zip(&states) {
+            for (cell_position, state) in line.iter_mut().zip(line_states) {
+                cell_position.cell = cell_position.cell.next_generation(state);
+            }
+        }
+        (self.births, self.deaths) = count_transitions(&current_states, &states);
+        self.previous_states = Some(current_states);
+    }
+
+    fn rule_output(&self) -> Vec<Vec<CellState>> {
+        let mut alive_counts = SummedAreaTable::default();
+        let alive_counts = self.count_alive_cells(&mut alive_counts).then_some(&alive_counts);
+        (UNIVERSE_START_INDEX..self.height)
+            .map(|y| (UNIVERSE_START_INDEX..self.width)
+                .map(|x| self.next_state_of(x, y, self.cells[y][x].cell.get_state(), alive_counts))
+                .collect()
+            )
+            .collect()
+    }
+
+    fn exclusive_or(states: &[Vec<CellState>], other_states: &[Vec<CellState>]) -> Vec<Vec<CellState>> {
+        states
+            .iter()
+            .zip(other_states)
+            .map(|(line, other_line)| line
+                .iter()
+                .zip(other_line)
+                .map(|(state, other_state)| match state.is_alive() != other_state.is_alive() {
+                    true => CellState::ALIVE,
+                    false => CellState::DEAD,
+                })
+                .collect()
+            )
+            .collect()
+    }
+
+    // Cells restart their age when put back in a previous state
+    fn cells_in_states(&self, states: &[Vec<CellState>]) -> Vec<Vec<CellPosition>> {
+        self
+            .cells
+            .iter()
+            .zip(states)
+            .map(|(line, line_states)| line
+                .iter()
+                .zip(line_states)
+                .map(|(cell_position, state)| CellPosition { cell: cell_position.cell.with_state(state), ..cell_position.clone() })
+                .collect()
+            )
+            .collect()
+    }
+
+    // Only the cells of the active region are computed, the other ones keeping
+    // their state, when the rule and the update mode allow it
+    fn step_at_once(&mut self) {
+        let mut births = 0;
+        let mut deaths = 0;
+        let mut active_region = self
+            .skips_unchanged_cells()
+            .then(|| ActiveRegion::resume(self.active_region.take(), self.width, self.height, &self.neighbourhood, &self.topology));
+        let mut alive_counts = std::mem::take(&mut self.alive_counts);
+        let counts_alive_cells = self.count_alive_cells(&mut alive_counts);
+        let mut next_cells = std::mem::take(&mut self.next_cells);
+        if next_cells.len() != self.height || next_cells.first().map(|line| line.len()) != self.cells.first().map(|line| line.len()) {
+            next_cells.clone_from(&self.cells);
+        }
+
+        for (y, line) in next_cells.iter_mut().enumerate() {
+            for (x, next_cell_position) in line.iter_mut().enumerate() {
+                let cell = &self.cells[y][x].cell;
+                next_cell_position.cell = match self.updates_now() {
+                    false => cell.next_generation(&cell.get_state()),
+                    true => {
+                        let next_state = match active_region.as_ref().is_none_or(|active_region| active_region.is_active(x, y)) {
+                            true => self.next_state_of(x, y, cell.get_state(), counts_alive_cells.then_some(&alive_counts)),
+                            false => cell.get_state(),
+                        };
+                        let next_cell = cell.next_generation(&next_state);
+                        match (cell.is_alive(), next_cell.is_alive()) {
+                            (false, true) => births += 1,
+                            (true, false) => deaths += 1,
+                            _ => {}
                         }
-                    }
-                    _ => {
-                        Cell::new_random_state()
+                        if let Some(active_region) = active_region.as_mut() {
+                            active_region.record(x, y, &cell.get_state(), &next_state);
+                        }
+                        next_cell
                     }
                 };
-
-                line.push(CellPosition {
-                    x,
-                    y,
-                    cell,
-                });
             }
+        }
 
-            new_cells.push(line);
+        self.next_cells = std::mem::replace(&mut self.cells, next_cells);
+        self.alive_counts = alive_counts;
+        self.active_region = active_region;
+        self.births = births;
+        self.deaths = deaths;
+    }
+
+    // Every cell sees the states its neighbours took before it in the step
+    fn step_in_random_order(&mut self) {
+        let mut coordinates = (UNIVERSE_START_INDEX..self.height)
+            .flat_map(|y| (UNIVERSE_START_INDEX..self.width).map(move |x| (x, y)))
+            .collect::<Vec<(usize, usize)>>();
+        coordinates.shuffle(&mut *self.rng.borrow_mut());
+
+        let states_before_step = self.states();
+        for (x, y) in coordinates {
+            let next_state = self.next_state_of(x, y, self.cells[y][x].cell.get_state(), None);
+            self.cells[y][x].cell = self.cells[y][x].cell.next_generation(&next_state);
         }
+        (self.births, self.deaths) = count_transitions(&states_before_step, &self.states());
+    }
 
+    // Second-order cells also depend on their state two generations back
+    fn skips_unchanged_cells(&self) -> bool {
+        ActiveRegion::can_skip(self.rule.as_ref(), &self.update_mode) && self.previous_states.is_none()
+    }
+
+    fn updates_now(&self) -> bool {
+        match self.update_mode {
+            UpdateMode::Probabilistic(probability) => self.rng.borrow_mut().gen_bool(probability),
+            _ => true,
+        }
+    }
+
+    fn next_generation(&self, cells: Vec<Vec<CellPosition>>, births: usize, deaths: usize) -> Universe {
         Universe {
             width: self.width,
             height: self.height,
-            cells: new_cells,
+            cells,
+            next_cells: vec![],
+            alive_counts: SummedAreaTable::default(),
+            rule: Rc::clone(&self.rule),
+            neighbourhood: self.neighbourhood.clone(),
+            topology: self.topology.clone(),
+            update_mode: self.update_mode.clone(),
+            rng: self.rng.clone(),
+            previous_states: self.previous_states.clone(),
+            active_region: self.active_region.clone().map(|mut active_region| {
+                active_region.mark_all_changed();
+                active_region
+            }),
+            expansion: self.expansion,
+            history: self.history.clone(),
+            generation: self.generation + 1,
+            births,
+            deaths,
         }
     }
 
-    fn next_state_of(&self, cell_state: CellState, neighbour_state: Vec<CellState>) -> CellState {
-        let alive_neighbours_count = neighbour_state
-            .iter()
-            .filter(|state| state == &&CellState::ALIVE)
-            .count();
-
-        match alive_neighbours_count {
-            n if n < 2 || n > 3 => {
-                CellState::DEAD
-            }
-            3 => {
-                CellState::ALIVE
+    fn next_state_of(&self, x: usize, y: usize, cell_state: CellState, alive_counts: Option<&SummedAreaTable>) -> CellState {
+        match (self.rule.as_totalistic(), alive_counts) {
+            (Some(rule), Some(alive_counts)) => {
+                let range = self.neighbourhood.range();
+                let alive_neighbours = alive_counts.alive_around(x + range, y + range, range, &cell_state);
+                rule.next_state_from_count(&cell_state, alive_neighbours)
             }
-            _ => {
-                cell_state.clone()
+            _ => self.rule.next_state(&cell_state, &self.get_neighbours_of(x, y))
+        }
+    }
+
+    // Counting alive neighbours over square neighbourhoods takes constant time
+    // with a summed-area table, instead of growing with the square of the range.
+    // The table spans the cells beyond the edges as well, as the topology sees
+    // them. False when the rule or the neighbourhood does not allow it.
+    fn count_alive_cells(&self, alive_counts: &mut SummedAreaTable) -> bool {
+        match (self.rule.as_totalistic(), &self.neighbourhood) {
+            (Some(_), Neighbourhood::Moore(range)) => {
+                let range = *range;
+                alive_counts.count(self.width + 2 * range, self.height + 2 * range, |x, y| {
+                    let location = self.topology.locate(x as i64 - range as i64, y as i64 - range as i64, self.width, self.height);
+                    self.state_at(&location).is_some_and(|state| state.is_alive())
+                });
+                true
             }
+            _ => false
         }
     }
 
-    fn get_neighbours_states_of(&self, x: usize, y: usize) -> Vec<CellState> {
-        let column_neighbours_start = if y > 0 { y - 1 } else { 0 };
-        let column_neighbours_end = if y + 1 < self.height { y + 1 } else { self.height - 1 };
-
-        (column_neighbours_start..=column_neighbours_end)
-            .fold(vec![], |mut acc, column_index| {
-                let line_neighbours_start = if x > 0 { x - 1 } else { 0 };
-                let line_neighbours_end = if x + 1 < self.width { x + 1 } else { self.width - 1 };
-
-                (line_neighbours_start..=line_neighbours_end)
-                    .for_each(|line_index| {
-                        match self.cells.get(column_index) {
-                            None => {}
-                            Some(column) => {
-                                match column.get(line_index) {
-                                    None => {}
-                                    Some(neighbour) => {
-                                        if !(column_index == y && line_index == x) {
-                                            acc.push(neighbour.cell.get_state());
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    });
+    pub fn get_neighbours_states_of(&self, x: usize, y: usize) -> Vec<CellState> {
+        self
+            .get_neighbours_of(x, y)
+            .into_iter()
+            .map(|(state, _)| state)
+            .collect()
+    }
 
-                acc
+    pub fn get_neighbours_of(&self, x: usize, y: usize) -> Vec<(CellState, RelativePosition)> {
+        self
+            .neighbours_locations_of(x, y)
+            .into_iter()
+            .filter_map(|(location, position)| self.state_at(&location).map(|state| (state, position)))
+            .collect()
+    }
+
+    fn neighbours_locations_of(&self, x: usize, y: usize) -> Vec<(Location, RelativePosition)> {
+        self
+            .neighbourhood
+            .offsets()
+            .into_iter()
+            .map(|(offset_x, offset_y)| {
+                let location = self.topology.locate(x as i64 + offset_x as i64, y as i64 + offset_y as i64, self.width, self.height);
+                (location, RelativePosition::from_offset(offset_x, offset_y))
             })
-            .into_iter()                    // Iterate over the outer vector
-            .collect::<Vec<CellState>>()        // Collect it back into a Vec<String>
+            .filter(|(location, _)| location != &Location::Outside)
+            .collect()
     }
 
+    fn state_at(&self, location: &Location) -> Option<CellState> {
+        match location {
+            Location::Cell(x, y) => Some(self.cells[*y][*x].cell.get_state()),
+            Location::Border(state) => Some(state.clone()),
+            Location::Outside => None,
+        }
+    }
+
+    #[cfg(test)]
     fn count_neighbours_of(&self, x: usize, y: usize) -> usize {
-        let column_neighbours_start = if y > 0 { y - 1 } else { 0 };
-        let column_neighbours_end = if y + 1 < self.height { y + 1 } else { self.height - 1 };
-
-        (column_neighbours_start..=column_neighbours_end)
-            .fold(0, |acc_columns, column_index| {
-                let line_neighbours_start = if x > 0 { x - 1 } else { 0 };
-                let line_neighbours_end = if x + 1 < self.width { x + 1 } else { self.width - 1 };
-
-                acc_columns + (line_neighbours_start..=line_neighbours_end)
-                    .fold(0, |acc_lines, line_index| {
-                        match self.cells.get(column_index) {
-                            None => acc_lines,
-                            Some(column) => {
-                                match column.get(line_index) {
-                                    None => acc_lines,
-                                    Some(_) => {
-                                        if !(column_index == y && line_index == x) {
-                                            acc_lines + 1
-                                        } else {
-                                            acc_lines
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    })
-            })
+        self.neighbours_locations_of(x, y).len()
     }
 
+    #[cfg(test)]
     fn neighbours_positions_of(&self, x: usize, y: usize) -> String {
-        let column_neighbours_start = if y > 0 { y - 1 } else { 0 };
-        let column_neighbours_end = if y + 1 < self.height { y + 1 } else { self.height - 1 };
-
-        (column_neighbours_start..=column_neighbours_end)
-            .fold(vec![], |mut acc_columns, column_index| {
-                let line_neighbours_start = if x > 0 { x - 1 } else { 0 };
-                let line_neighbours_end = if x + 1 < self.width { x + 1 } else { self.width - 1 };
-
-                acc_columns.push((line_neighbours_start..=line_neighbours_end)
-                    .fold(vec![], |mut acc_lines, line_index| {
-                        match self.cells.get(column_index) {
-                            None => acc_lines,
-                            Some(column) => {
-                                match column.get(line_index) {
-                                    None => acc_lines,
-                                    Some(_) => {
-                                        if !(column_index == y && line_index == x) {
-                                            acc_lines.push(RelativePosition::get_position_from(x, y, line_index, column_index).print());
-                                        }
-                                        acc_lines
-                                    }
-                                }
-                            }
-                        }
-                    }));
-
-                acc_columns
-            })
-            .into_iter()                    // Iterate over the outer vector
-            .map(|inner| inner.join(","))    // Join each inner vector with spaces
-            .collect::<Vec<String>>()        // Collect it back into a Vec<String>
+        self
+            .neighbours_locations_of(x, y)
+            .iter()
+            .map(|(_, position)| position.print())
+            .collect::<Vec<String>>()
             .join(",")
     }
 
+    pub fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        Some(self.cells.get(y)?.get(x)?.cell.get_state())
+    }
+
+    /// Changes a cell between ticks, leaving the history and statistics as they were.
+    pub fn set(&mut self, x: usize, y: usize, state: CellState) -> Result<(), String> {
+        let cell_position = self
+            .cells
+            .get_mut(y)
+            .and_then(|line| line.get_mut(x))
+            .ok_or(format!("Cell ({}, {}) is outside of the {}x{} universe", x, y, self.width, self.height))?;
+        cell_position.cell = cell_position.cell.with_state(&state);
+        self.mark_changed(x, y);
+        Ok(())
+    }
+
+    pub fn toggle(&mut self, x: usize, y: usize) -> Result<(), String> {
+        let state = self.get(x, y).map_or(CellState::DEAD, |state| state.toggled());
+        self.set(x, y, state)
+    }
+
+    pub fn clear(&mut self) {
+        self.fill_rect(0, 0, self.width, self.height, CellState::DEAD);
+    }
+
+    /// Sets every cell of the rectangle, the part of it outside of the universe being left out.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, state: CellState) {
+        for line in self.cells.iter_mut().skip(y).take(height) {
+            for cell_position in line.iter_mut().skip(x).take(width) {
+                cell_position.cell = cell_position.cell.with_state(&state);
+                if let Some(active_region) = self.active_region.as_mut() {
+                    active_region.mark_changed(cell_position.x, cell_position.y);
+                }
+            }
+        }
+    }
+
+    /// Places a pattern into the universe between ticks, as `set` would do cell by cell.
+    pub fn stamp(&mut self, stamp: &Stamp) {
+        for (x, y, state) in stamp.changes(&self.states()) {
+            self.cells[y][x].cell = self.cells[y][x].cell.with_state(&state);
+            self.mark_changed(x, y);
+        }
+    }
+
+    /// Changes the size of the universe, the anchor telling which part of it
+    /// stays in place. Cells are added dead or cut on the other sides, and the
+    /// history follows the cells that are kept.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Cannot resize the universe to {}x{}, it needs at least one cell", width, height));
+        }
+        self.topology.check_size(width, height)?;
+        let offset = anchor.offset((self.width, self.height), (width, height));
+        self.move_cells(width, height, offset);
+        Ok(())
+    }
+
+    fn move_cells(&mut self, width: usize, height: usize, offset: (i64, i64)) {
+        // New cells have their age tracked when the other ones do
+        let dead_cell = self.cells[0][0].cell.with_state(&CellState::DEAD);
+        self.cells = resize::moved(std::mem::take(&mut self.cells), (width, height), offset, |x, y, former| CellPosition {
+            x,
+            y,
+            cell: former.map_or_else(|| dead_cell.clone(), |former| former.cell),
+        });
+        self.previous_states = self
+            .previous_states
+            .take()
+            .map(|states| resize::moved(states, (width, height), offset, |_, _, former| former.unwrap_or(CellState::DEAD)));
+        self.history.translate(offset, (width, height));
+        self.width = width;
+        self.height = height;
+        self.next_cells = vec![];
+        self.active_region = None;
+    }
+
+    // Grows the universe beyond the edges alive cells are within reach of, so
+    // that the next generation has room for the cells they may give birth to
+    fn expand_towards_alive_cells(&mut self) {
+        let Some(expansion) = self.expansion else {
+            return;
+        };
+        let alive = self.iter_live().map(|(x, y, _)| (x, y));
+        if let Some(((width, height), offset)) = resize::expansion(alive, (self.width, self.height), self.neighbourhood.range(), expansion, &self.topology) {
+            self.move_cells(width, height, offset);
+        }
+    }
+
+    fn mark_changed(&mut self, x: usize, y: usize) {
+        if let Some(active_region) = self.active_region.as_mut() {
+            active_region.mark_changed(x, y);
+        }
+    }
+
+    /// Coordinates and states of the alive cells, line by line.
+    pub fn iter_live(&self) -> impl Iterator<Item=(usize, usize, CellState)> + '_ {
+        self
+            .cells
+            .iter()
+            .flatten()
+            .filter(|cell_position| cell_position.cell.is_alive())
+            .map(|cell_position| (cell_position.x, cell_position.y, cell_position.cell.get_state()))
+    }
+
     pub fn print(&self) -> Vec<String> {
         self
             .cells
@@ -191,6 +512,35 @@ impl Universe {
             .collect()
     }
 
+    pub fn states(&self) -> Vec<Vec<CellState>> {
+        self
+            .cells
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|cell_position| cell_position.cell.get_state())
+                .collect()
+            )
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        GenerationStatistics::from_states(self.generation, self.births, self.deaths, &self.states())
+    }
+
+    #[cfg(test)]
     fn print_check(&self) -> Vec<String> {
         self
             .cells
@@ -204,21 +554,221 @@ impl Universe {
             .collect()
     }
 
+    pub fn rule(&self) -> Rc<dyn Rule> {
+        Rc::clone(&self.rule)
+    }
+
+    pub fn neighbourhood(&self) -> &Neighbourhood {
+        &self.neighbourhood
+    }
+
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    pub fn update_mode(&self) -> &UpdateMode {
+        &self.update_mode
+    }
+
+    /// Full state of the universe, to be saved and resumed later, random
+    /// number generators and history included.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            width: self.width,
+            height: self.height,
+            rule: self.rule.name(),
+            neighbourhood: self.neighbourhood.to_string(),
+            topology: self.topology.to_string(),
+            update_mode: self.update_mode.to_string(),
+            generation: self.generation,
+            births: self.births,
+            deaths: self.deaths,
+            rng: RngState::of(&self.rng.borrow()),
+            noise: self.rule.as_stochastic().map(Noise::of),
+            history: self.history.clone(),
+            expansion: self.expansion,
+            cells: self.states(),
+            ages: self.ages(),
+            previous_states: self.previous_states.clone(),
+        }
+    }
+
+    /// Tiles computed at the last step, when cells whose neighbourhood did not change are skipped.
+    pub fn active_region(&self) -> Option<&ActiveRegion> {
+        self.active_region.as_ref()
+    }
+
+    pub fn is_second_order(&self) -> bool {
+        self.previous_states.is_some()
+    }
+
+    /// Ages of the cells, when tracked.
+    pub fn ages(&self) -> Option<Vec<Vec<CellAge>>> {
+        self
+            .cells
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|cell_position| cell_position.cell.age())
+                .collect()
+            )
+            .collect()
+    }
+
+    pub fn age_of(&self, x: usize, y: usize) -> Option<CellAge> {
+        self.cells.get(y)?.get(x)?.cell.age()
+    }
+
     /*
         STATIC
      */
     pub fn new(width: usize, height: usize) -> Universe {
-        let states = Self::generate_base_states(width, height);
+        let states = Self::generate_base_states(width, height, &mut rand::thread_rng());
         Universe::new_from_cell_states(states)
     }
 
-    fn generate_base_states(width: usize, height: usize) -> Vec<Vec<CellState>> {
+    /// Random universe drawn from the seed, which then drives its updates as well.
+    pub fn new_with_seed(width: usize, height: usize, seed: u64) -> Universe {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let states = Self::generate_base_states(width, height, &mut rng);
+        Universe {
+            rng: RefCell::new(rng),
+            ..Universe::new_from_cell_states(states)
+        }
+    }
+
+    /// Uses the rule, along with its neighbourhood when it is defined on one.
+    pub fn with_rule(self, rule: Rc<dyn Rule>) -> Universe {
+        Universe {
+            neighbourhood: rule.neighbourhood().unwrap_or(self.neighbourhood),
+            rule,
+            active_region: None,
+            ..self
+        }
+    }
+
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Universe {
+        Universe {
+            neighbourhood,
+            active_region: None,
+            ..self
+        }
+    }
+
+    /// Spheres need a square universe, as `Topology::check_size` tells.
+    pub fn with_topology(self, topology: Topology) -> Universe {
+        Universe {
+            topology,
+            active_region: None,
+            ..self
+        }
+    }
+
+    pub fn with_update_mode(self, update_mode: UpdateMode) -> Universe {
+        Universe {
+            update_mode,
+            active_region: None,
+            ..self
+        }
+    }
+
+    /// Tracks how long every cell has been alive or dead from now on.
+    pub fn with_age_tracking(self) -> Universe {
+        let cells = self
+            .cells
+            .into_iter()
+            .map(|line| line
+                .into_iter()
+                .map(|cell_position| CellPosition { cell: cell_position.cell.with_age_tracking(), ..cell_position })
+                .collect()
+            )
+            .collect();
+        Universe {
+            cells,
+            ..self
+        }
+    }
+
+    /// Makes the universe reversible: every generation becomes the rule output
+    /// XOR the generation before it, the one before the first being empty.
+    /// Cells are then all updated at once, whatever the update mode.
+    pub fn with_second_order(self) -> Universe {
+        Universe {
+            previous_states: Some(vec![vec![CellState::DEAD; self.width]; self.height]),
+            ..self
+        }
+    }
+
+    /// Keeps the changes of the last ticks, up to the limit, to step back through them.
+    pub fn with_history(self, limit: usize) -> Universe {
+        Universe {
+            history: History::new(limit),
+            ..self
+        }
+    }
+
+    /// Grows the universe before every step by at least the given number of
+    /// cells beyond any edge alive cells are within reach of, as if it had
+    /// none. Stepping back keeps the universe at its larger size.
+    pub fn with_auto_expand(self, cells: usize) -> Universe {
+        Universe {
+            expansion: Some(cells),
+            ..self
+        }
+    }
+
+    /// Draws the same update order and updated cells at every run with the same seed.
+    pub fn with_seed(self, seed: u64) -> Universe {
+        Universe {
+            rng: RefCell::new(ChaCha12Rng::seed_from_u64(seed)),
+            ..self
+        }
+    }
+
+    /// Universe as it was saved, going on from where its random number
+    /// generators were and able to step back through its history.
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Universe, String> {
+        let (rule, neighbourhood, topology, update_mode) = snapshot.settings()?;
+        let universe = Universe::new_from_cell_states(snapshot.cells)
+            .with_rule(rule)
+            .with_neighbourhood(neighbourhood)
+            .with_topology(topology)
+            .with_update_mode(update_mode);
+        let cells = match snapshot.ages {
+            Some(ages) => universe
+                .cells
+                .into_iter()
+                .zip(ages)
+                .map(|(line, line_ages)| line
+                    .into_iter()
+                    .zip(line_ages)
+                    .map(|(cell_position, age)| CellPosition { cell: cell_position.cell.with_age(age), ..cell_position })
+                    .collect()
+                )
+                .collect(),
+            None => universe.cells,
+        };
+
+        Ok(Universe {
+            cells,
+            previous_states: snapshot.previous_states,
+            rng: RefCell::new(snapshot.rng.restore()),
+            history: snapshot.history,
+            expansion: snapshot.expansion,
+            generation: snapshot.generation,
+            births: snapshot.births,
+            deaths: snapshot.deaths,
+            ..universe
+        })
+    }
+
+    fn generate_base_states(width: usize, height: usize, rng: &mut impl Rng) -> Vec<Vec<CellState>> {
         let mut states: Vec<Vec<CellState>> = vec![];
 
         for _ in 0..height {
             let mut line_states: Vec<CellState> = vec![];
             for _ in 0..width {
-                let rand = rand::thread_rng().gen_range(0..2);
+                let rand = rng.gen_range(0..2);
                 let state = match rand {
                     0 => CellState::DEAD,
                     _ => CellState::ALIVE,
@@ -233,23 +783,18 @@ impl Universe {
 
     pub fn new_from_states(states: &Vec<&str>) -> Universe {
         Self::new_from_cell_states(states
-            .into_iter()
+            .iter()
             .map(|line| {
                 line
                     .chars()
                     .filter(|cell| cell != &' ')
-                    .map(|cell| {
-                        match cell {
-                            'x' => CellState::ALIVE,
-                            _ => CellState::DEAD
-                        }
-                    })
+                    .map(CellState::from_char)
                     .collect::<Vec<CellState>>()
             })
             .collect::<Vec<Vec<CellState>>>())
     }
 
-    fn new_from_cell_states(states: Vec<Vec<CellState>>) -> Universe {
+    pub fn new_from_cell_states(states: Vec<Vec<CellState>>) -> Universe {
         let height = states.len();
         let width = states[0].len();
 
@@ -258,7 +803,7 @@ impl Universe {
         for y in UNIVERSE_START_INDEX..height {
             let mut line: Vec<CellPosition> = vec![];
             for x in UNIVERSE_START_INDEX..width {
-                let mut cell = match states.get(y) {
+                let cell = match states.get(y) {
                     Some(line_of_states) => {
                         match line_of_states.get(x) {
                             None => Cell::new_random_state(),
@@ -284,21 +829,87 @@ impl Universe {
             width,
             height,
             cells,
+            next_cells: vec![],
+            alive_counts: SummedAreaTable::default(),
+            rule: Rc::new(LifeLikeRule::conway()),
+            neighbourhood: Neighbourhood::default(),
+            topology: Topology::default(),
+            update_mode: UpdateMode::default(),
+            rng: RefCell::new(ChaCha12Rng::from_entropy()),
+            previous_states: None,
+            active_region: None,
+            expansion: None,
+            history: History::default(),
+            generation: 0,
+            births: 0,
+            deaths: 0,
         }
     }
 }
 
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        Universe::from_snapshot(Snapshot::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl Simulation for Universe {
+    fn advance(&mut self) {
+        self.step();
+    }
+
+    fn states(&self) -> Vec<Vec<CellState>> {
+        Universe::states(self)
+    }
+
+    fn statistics(&self) -> GenerationStatistics {
+        Universe::statistics(self)
+    }
+
+    fn print(&self) -> Vec<String> {
+        Universe::print(self)
+    }
+
+    fn ages(&self) -> Option<Vec<Vec<CellAge>>> {
+        Universe::ages(self)
+    }
+}
+
 #[cfg(test)]
 mod universe_tests {
+    use crate::common::catalogue;
+    use crate::common::cell_age::CellAge;
     use crate::common::cell_state::CellState;
+    use std::rc::Rc;
+
+    use crate::common::neighbourhood::Neighbourhood;
+    use crate::common::relative_position::RelativePosition;
+    use crate::common::resize::Anchor;
+    use crate::common::rule::LifeLikeRule;
+    use crate::common::snapshot;
+    use crate::common::larger_than_life::LargerThanLifeRule;
+    use crate::common::pattern::Pattern;
+    use crate::common::stamp::{CombineMode, Stamp};
+    use crate::common::statistics::BoundingBox;
+    use crate::common::stochastic::StochasticRule;
+    use crate::common::topology::Topology;
+    use crate::common::transform::Transform;
+    use crate::common::update_mode::UpdateMode;
     use crate::nopointer::universe::Universe;
+    use crate::smartpointers::universe as sp;
 
     #[test]
     fn should_be_able_to_generate_a_monocellular_universe() {
         let universe = Universe::new(1, 1);
 
         for line_to_print in universe.print() {
-            assert_eq!(line_to_print, "x");
+            assert!(line_to_print == "x" || line_to_print == "o");
             println!("{:?}", line_to_print);
         }
     }
@@ -435,7 +1046,7 @@ mod universe_tests {
         print_universe(&universe);
 
         let number_of_ticks = 10;
-        for x in 0..=number_of_ticks {
+        for _ in 0..=number_of_ticks {
             println!("Tick");
             let new_universe = universe.tick();
             print_universe(&new_universe);
@@ -443,6 +1054,424 @@ mod universe_tests {
         }
     }
 
+    #[test]
+    fn should_report_statistics_of_the_last_tick() {
+        let state = vec![
+            "o x o",
+            "o x o",
+            "o x o"
+        ];
+        let universe = Universe::new_from_states(&state);
+
+        let statistics = universe.tick().statistics();
+
+        assert_eq!(statistics.generation, 1);
+        assert_eq!(statistics.population, 3);
+        assert_eq!(statistics.births, 2);
+        assert_eq!(statistics.deaths, 2);
+        assert_eq!(statistics.bounding_box, Some(BoundingBox { min_x: 0, min_y: 1, max_x: 2, max_y: 1 }));
+    }
+
+    #[test]
+    fn should_show_decaying_cells_with_a_generations_rule() {
+        let state = vec![
+            "o o o o",
+            "o x x o",
+            "o o o o"
+        ];
+        let universe = Universe::new_from_states(&state).with_rule(Rc::new(LifeLikeRule::parse("B2/S/C3").unwrap()));
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.print(), vec!["o x x o", "o 1 1 o", "o x x o"]);
+        assert_eq!(new_universe.tick().print()[1], "x o o x");
+    }
+
+    #[test]
+    fn should_use_selected_neighbourhood() {
+        let universe = Universe::new(3, 3);
+
+        let von_neumann = universe.with_neighbourhood(Neighbourhood::VonNeumann(1));
+        assert_eq!(von_neumann.print_check()[1], "(10)(3:N,E,S) (11)(4:N,W,E,S) (12)(3:N,W,S)");
+
+        let hexagonal = von_neumann.with_neighbourhood(Neighbourhood::Hexagonal);
+        assert_eq!(hexagonal.print_check()[1], "(10)(4:N,E,S,SE) (11)(6:NW,N,W,E,S,SE) (12)(4:NW,N,W,S)");
+    }
+
+    #[test]
+    fn should_reach_distant_neighbours_with_a_larger_range() {
+        let universe = Universe::new(5, 5).with_neighbourhood(Neighbourhood::Moore(2));
+
+        assert_eq!(universe.get_neighbours_of(2, 2).len(), 24);
+        assert_eq!(universe.get_neighbours_of(0, 0).len(), 8);
+        assert_eq!(universe.get_neighbours_of(0, 0)[7].1, RelativePosition::Offset { x: 2, y: 2 });
+    }
+
+    #[test]
+    fn should_apply_rule_to_von_neumann_neighbours_only() {
+        let state = vec![
+            "o o o",
+            "o x o",
+            "o o o"
+        ];
+        let universe = Universe::new_from_states(&state)
+            .with_rule(Rc::new(LifeLikeRule::parse("B1/S").unwrap()))
+            .with_neighbourhood(Neighbourhood::VonNeumann(1));
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.print(), vec!["o x o", "x o x", "o x o"]);
+    }
+
+    #[test]
+    fn should_bring_a_glider_back_around_a_torus() {
+        let states = catalogue::pattern("glider").unwrap().centered_in(5, 5);
+        let mut universe = Universe::new_from_cell_states(states.clone()).with_topology(Topology::Torus);
+
+        for _ in 0..20 {
+            universe = universe.tick();
+        }
+
+        assert_eq!(universe.states(), states);
+    }
+
+    #[test]
+    fn should_count_a_fixed_border_as_neighbours() {
+        let state = vec![
+            "o o o",
+            "o o o",
+            "o o o"
+        ];
+        let universe = Universe::new_from_states(&state).with_topology(Topology::Bordered(CellState::ALIVE));
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.print(), vec!["o x o", "x o x", "o x o"]);
+    }
+
+    #[test]
+    fn should_count_neighbours_across_edges_alike_with_summed_area_tables() {
+        let rule = Rc::new(LargerThanLifeRule::parse("R3,C0,M1,S8..20,B9..14,NM").unwrap());
+
+        for topology in [Topology::Torus, Topology::KleinBottle, Topology::CrossSurface, Topology::Sphere, Topology::Bordered(CellState::ALIVE)] {
+            let mut universe = Universe::new(9, 9).with_rule(rule.clone()).with_topology(topology.clone());
+            let linked_universe = sp::Universe::new_with_defined_states(universe.states()).with_rule(rule.clone()).with_topology(topology);
+
+            for _ in 0..4 {
+                universe = universe.tick();
+                linked_universe.tick();
+                assert_eq!(universe.print(), linked_universe.print());
+            }
+        }
+    }
+
+    #[test]
+    fn should_track_ages_of_cells_on_demand() {
+        let state = vec![
+            "o o o",
+            "x x x",
+            "o o o"
+        ];
+        let universe = Universe::new_from_states(&state);
+        assert_eq!(universe.ages(), None);
+
+        let new_universe = universe.with_age_tracking().tick().tick();
+
+        assert_eq!(new_universe.age_of(1, 1), Some(CellAge::Alive(3)));
+        assert_eq!(new_universe.age_of(0, 1), Some(CellAge::Alive(1)));
+        assert_eq!(new_universe.age_of(1, 0), Some(CellAge::Dead(1)));
+        assert_eq!(new_universe.age_of(0, 0), Some(CellAge::NeverAlive));
+        assert_eq!(new_universe.ages().unwrap()[1][2], CellAge::Alive(1));
+    }
+
+    #[test]
+    fn should_keep_cells_that_are_not_updated() {
+        let universe = Universe::new(8, 8).with_update_mode(UpdateMode::Probabilistic(0.0));
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.print(), universe.print());
+        assert_eq!(new_universe.generation(), 1);
+    }
+
+    #[test]
+    fn should_let_cells_see_the_states_already_updated_in_random_order() {
+        // Whichever cell of the pair is updated first dies, leaving the other one alone
+        let universe = Universe::new_from_states(&vec!["x x"])
+            .with_rule(Rc::new(LifeLikeRule::parse("B/S0").unwrap()))
+            .with_update_mode(UpdateMode::RandomOrder)
+            .with_seed(5);
+
+        let new_universe = universe.tick();
+
+        assert_eq!(new_universe.statistics().population, 1);
+    }
+
+    #[test]
+    fn should_update_alike_in_both_engines_with_the_same_seed() {
+        for update_mode in [UpdateMode::RandomOrder, UpdateMode::Probabilistic(0.5)] {
+            let mut universe = Universe::new_with_seed(10, 10, 42).with_update_mode(update_mode.clone());
+            let linked_universe = sp::Universe::new_with_seed(10, 10, 42).with_update_mode(update_mode);
+
+            for _ in 0..5 {
+                universe = universe.tick();
+                linked_universe.tick();
+                assert_eq!(universe.print(), linked_universe.print());
+            }
+        }
+    }
+
+    #[test]
+    fn should_start_second_order_runs_as_first_order_ones() {
+        let universe = Universe::new_with_seed(8, 8, 5);
+        let second_order = Universe::new_from_cell_states(universe.states()).with_second_order();
+
+        assert_eq!(second_order.tick().print(), universe.tick().print());
+    }
+
+    #[test]
+    fn should_go_back_to_the_first_generation_of_a_second_order_run() {
+        let first = Universe::new_with_seed(12, 10, 9).with_topology(Topology::Torus).with_second_order();
+        let mut universe = first.tick();
+        for _ in 0..9 {
+            universe = universe.tick();
+        }
+
+        for _ in 0..10 {
+            universe = universe.tick_back().unwrap();
+        }
+
+        assert_eq!(universe.print(), first.print());
+        assert_eq!(universe.generation(), 0);
+        assert_eq!(universe.tick().print(), first.tick().print());
+    }
+
+    #[test]
+    fn should_only_step_backwards_in_second_order() {
+        assert!(Universe::new(4, 4).tick_back().is_err());
+    }
+
+    #[test]
+    fn should_step_back_through_the_history() {
+        let first = Universe::new_with_seed(10, 10, 4).with_history(5);
+        let mut universe = first.tick();
+        let second = universe.tick();
+        universe = second.tick();
+
+        universe = universe.step_back().unwrap();
+
+        assert_eq!(universe.print(), second.print());
+        assert_eq!(universe.statistics(), second.statistics());
+        assert_eq!(universe.history().len(), 2);
+        assert_eq!(universe.goto(0).unwrap().print(), first.print());
+    }
+
+    #[test]
+    fn should_not_go_back_beyond_the_history_limit() {
+        let mut universe = Universe::new_with_seed(10, 10, 4).with_history(2);
+        for _ in 0..5 {
+            universe = universe.tick();
+        }
+
+        assert_eq!(universe.history().earliest_generation(), Some(3));
+        assert_eq!(universe.goto(3).unwrap().generation(), 3);
+        assert!(universe.goto(2).is_err());
+        assert!(Universe::new(4, 4).step_back().is_err());
+    }
+
+    #[test]
+    fn should_keep_second_order_runs_reversible_after_stepping_back() {
+        let first = Universe::new_with_seed(10, 10, 6).with_second_order().with_history(5);
+        let universe = first.tick().tick().tick().step_back().unwrap();
+
+        assert_eq!(universe.tick_back().unwrap().tick_back().unwrap().print(), first.print());
+    }
+
+    #[test]
+    fn should_stamp_patterns_into_a_universe() {
+        let mut universe = Universe::new_from_states(&vec!["o o o o", "o o o o", "x o o o"]);
+        let blinker = Pattern::from_text(&["x x x"]);
+
+        universe.stamp(&Stamp::new(blinker.clone()).at(2, 0).with_transform(Transform::Rotate90));
+        universe.stamp(&Stamp::new(blinker).at(-2, 2).with_mode(CombineMode::Xor));
+
+        assert_eq!(universe.print(), vec!["o o x o", "o o x o", "o o x o"]);
+    }
+
+    #[test]
+    fn should_edit_cells_between_ticks() {
+        let mut universe = Universe::new_from_states(&vec!["x o o o", "o o o o", "o o o o"]);
+
+        universe.clear();
+        universe.fill_rect(1, 0, 1, 5, CellState::ALIVE);
+        universe.set(3, 2, CellState::DYING(1)).unwrap();
+        universe.toggle(3, 2).unwrap();
+        universe.toggle(1, 0).unwrap();
+
+        assert_eq!(universe.print(), vec!["o o o o", "o x o o", "o x o x"]);
+        assert_eq!(universe.get(3, 2), Some(CellState::ALIVE));
+        assert_eq!(universe.get(4, 0), None);
+        assert!(universe.set(0, 3, CellState::ALIVE).is_err());
+        assert_eq!(universe.iter_live().map(|(x, y, _)| (x, y)).collect::<Vec<(usize, usize)>>(), vec![(1, 1), (1, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn should_step_in_place_as_tick_does() {
+        let setups = [
+            Universe::new_with_seed(12, 9, 2),
+            Universe::new_with_seed(12, 9, 2).with_update_mode(UpdateMode::RandomOrder),
+            Universe::new_with_seed(12, 9, 2).with_update_mode(UpdateMode::Probabilistic(0.5)),
+            Universe::new_with_seed(12, 9, 2).with_rule(Rc::new(LargerThanLifeRule::parse("R2,C0,M1,S6..12,B7..9,NM").unwrap())),
+            Universe::new_with_seed(12, 9, 2).with_second_order().with_history(3),
+        ];
+
+        for mut stepped in setups {
+            let mut ticked = stepped.clone();
+            for _ in 0..6 {
+                stepped.step();
+                ticked = ticked.tick();
+                assert_eq!(stepped.print(), ticked.print());
+                assert_eq!(stepped.statistics(), ticked.statistics());
+            }
+            if stepped.history().is_recording() {
+                assert_eq!(stepped.step_back().unwrap().print(), ticked.step_back().unwrap().print());
+            }
+        }
+    }
+
+    #[test]
+    fn should_only_compute_the_tiles_around_changes() {
+        let mut universe = Universe::new_from_cell_states(vec![vec![CellState::DEAD; 64]; 64]).with_age_tracking();
+        universe.fill_rect(40, 41, 3, 1, CellState::ALIVE);
+        // Every cell is computed when some of them may not be updated, even if all are
+        let mut computed_in_full = universe.clone().with_update_mode(UpdateMode::Probabilistic(1.0));
+
+        for _ in 0..3 {
+            universe.step();
+            computed_in_full.step();
+        }
+        assert_eq!(universe.active_region().map(|active_region| active_region.active_tiles()), Some(9));
+
+        universe.set(0, 0, CellState::ALIVE).unwrap();
+        computed_in_full.set(0, 0, CellState::ALIVE).unwrap();
+        universe.step();
+        computed_in_full.step();
+
+        assert_eq!(universe.active_region().map(|active_region| active_region.active_tiles()), Some(13));
+        assert_eq!(universe.print(), computed_in_full.print());
+        assert_eq!(universe.ages(), computed_in_full.ages());
+        assert_eq!(universe.statistics(), computed_in_full.statistics());
+        assert!(computed_in_full.active_region().is_none());
+    }
+
+    #[test]
+    fn should_resize_around_the_anchor() {
+        let mut universe = Universe::new_from_states(&vec!["x o", "o x"]);
+
+        universe.resize(4, 3, Anchor::BottomRight).unwrap();
+        assert_eq!(universe.print(), vec!["o o o o", "o o x o", "o o o x"]);
+        assert_eq!(universe.iter_live().map(|(x, y, _)| (x, y)).collect::<Vec<(usize, usize)>>(), vec![(2, 1), (3, 2)]);
+
+        universe.resize(2, 2, Anchor::Centre).unwrap();
+        assert_eq!(universe.print(), vec!["o o", "o x"]);
+        assert_eq!((universe.width(), universe.height()), (2, 2));
+        assert!(universe.resize(0, 2, Anchor::TopLeft).is_err());
+
+        let mut sphere = universe.with_topology(Topology::Sphere);
+        assert!(sphere.resize(3, 2, Anchor::TopLeft).is_err());
+        assert!(sphere.resize(3, 3, Anchor::TopLeft).is_ok());
+    }
+
+    #[test]
+    fn should_keep_stepping_and_stepping_back_after_a_resize() {
+        let mut universe = Universe::new_from_states(&vec!["o o o", "x x x", "o o o"]).with_history(5);
+        universe.step();
+
+        universe.resize(5, 5, Anchor::Centre).unwrap();
+        universe.step();
+
+        let horizontal_blinker = vec!["o o o o o", "o o o o o", "o x x x o", "o o o o o", "o o o o o"];
+        assert_eq!(universe.print(), horizontal_blinker);
+        let first = universe.goto(0).unwrap();
+        assert_eq!(first.print(), horizontal_blinker);
+        assert_eq!(first.tick().print(), vec!["o o o o o", "o o x o o", "o o x o o", "o o x o o", "o o o o o"]);
+    }
+
+    #[test]
+    fn should_grow_as_alive_cells_reach_the_edges() {
+        let glider = catalogue::pattern("glider").unwrap();
+        let mut universe = Universe::new_from_cell_states(glider.centered_in(5, 5)).with_auto_expand(2);
+        let sp_universe = sp::Universe::new_with_defined_states(glider.centered_in(5, 5)).with_auto_expand(2);
+
+        for _ in 0..40 {
+            universe.step();
+            sp_universe.tick();
+            assert_eq!(universe.statistics().population, 5);
+        }
+
+        assert!(universe.width() > 12 && universe.height() > 12);
+        assert_eq!(sp_universe.print(), universe.print());
+    }
+
+    #[test]
+    fn should_go_on_alike_once_saved_and_restored() {
+        let universe = Universe::new_with_seed(16, 16, 8)
+            .with_neighbourhood(Neighbourhood::Hexagonal)
+            .with_topology(Topology::Torus)
+            .with_update_mode(UpdateMode::RandomOrder)
+            .with_age_tracking()
+            .with_history(3)
+            .tick()
+            .tick();
+        assert_eq!(universe.snapshot(), universe.snapshot());
+
+        let formats: [fn(&Universe) -> Universe; 2] = [
+            |universe| snapshot::from_json(&snapshot::to_json(universe).unwrap()).unwrap(),
+            |universe| snapshot::from_bytes(&snapshot::to_bytes(universe).unwrap()).unwrap(),
+        ];
+
+        for save_and_restore in formats {
+            let restored = save_and_restore(&universe);
+            assert_eq!(restored.statistics(), universe.statistics());
+            assert_eq!(restored.neighbourhood(), &Neighbourhood::Hexagonal);
+            assert_eq!(restored.ages(), universe.ages());
+            assert_eq!(restored.history(), universe.history());
+            assert_eq!(restored.step_back().unwrap().print(), universe.step_back().unwrap().print());
+            assert_eq!(restored.tick().tick().print(), universe.tick().tick().print());
+        }
+    }
+
+    #[test]
+    fn should_keep_the_noise_of_a_stochastic_rule_once_restored() {
+        let noisy = StochasticRule::new(Rc::new(LifeLikeRule::conway()), 0.6, 0.8).with_seed(3);
+        let universe = Universe::new_with_seed(16, 16, 8).with_rule(Rc::new(noisy)).tick();
+
+        let restored: Universe = snapshot::from_json(&snapshot::to_json(&universe).unwrap()).unwrap();
+        let noise = restored.snapshot().noise.unwrap();
+
+        assert_eq!((noise.birth_probability, noise.survival_probability), (0.6, 0.8));
+        assert_eq!(restored.tick().print(), universe.tick().print());
+    }
+
+    #[test]
+    fn should_keep_second_order_runs_reversible_once_restored() {
+        let first = Universe::new_with_seed(10, 10, 6).with_second_order();
+        let universe = first.tick().tick();
+
+        let restored = Universe::from_snapshot(universe.snapshot()).unwrap();
+
+        assert_eq!(restored.generation(), 2);
+        assert_eq!(restored.tick_back().unwrap().tick_back().unwrap().print(), first.print());
+        assert!(sp::Universe::from_snapshot(universe.snapshot()).is_err());
+    }
+
+    #[test]
+    fn should_go_forward_to_a_later_generation() {
+        let universe = Universe::new_with_seed(10, 10, 4);
+
+        assert_eq!(universe.goto(3).unwrap().print(), universe.tick().tick().tick().print());
+    }
+
     mod game_rules {
         use crate::nopointer::universe::Universe;
         use crate::nopointer::universe::universe_tests::print_universe;
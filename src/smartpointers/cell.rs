@@ -1,22 +1,26 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 use rand::Rng;
+use crate::common::cell_age::CellAge;
 use crate::common::cell_state::CellState;
 use crate::common::relative_position::RelativePosition;
+use crate::common::rule::{LifeLikeRule, Rule};
 
 #[derive(Debug, PartialEq)]
 pub struct Cell {
     state: CellState,
     next_state: CellState,
     neighbours: Vec<(Rc<RefCell<Cell>>, RelativePosition)>,
+    age: Option<CellAge>,
 }
 
 impl Cell {
     pub fn is_alive(&self) -> bool {
-        match self.state {
-            CellState::ALIVE => { true }
-            CellState::DEAD => { false }
-        }
+        self.state.is_alive()
+    }
+
+    pub fn get_state(&self) -> CellState {
+        self.state.clone()
     }
 
     pub fn is_dead(&self) -> bool {
@@ -29,43 +33,61 @@ impl Cell {
         }
     }
 
+    pub fn clear_neighbours(&mut self) {
+        self.neighbours.clear();
+    }
+
     pub fn number_of_neighbours(&self) -> usize {
         self.neighbours.len()
     }
 
     pub fn pretick(&mut self) {
-        let number_of_live_neighbours = self.count_live_neighbours();
-        match number_of_live_neighbours {
-            n if n < 2 || n > 3 => {
-                self.next_state = CellState::DEAD
-            }
-            3 => {
-                self.next_state = CellState::ALIVE
-            }
-            _ => {
-                self.next_state = match self.state {
-                    CellState::ALIVE => CellState::ALIVE,
-                    CellState::DEAD => CellState::DEAD,
-                }
-            }
-        }
+        self.pretick_with_rule(&LifeLikeRule::conway())
+    }
+
+    pub fn pretick_with_rule(&mut self, rule: &dyn Rule) {
+        self.next_state = rule.next_state(&self.state, &self.neighbours_states());
     }
 
     pub fn tick(&mut self) {
-        self.state = match self.next_state {
-            CellState::ALIVE => CellState::ALIVE,
-            CellState::DEAD => CellState::DEAD
+        self.state = self.next_state.clone();
+        self.age = self.age.map(|age| age.next(&self.state));
+    }
+
+    /// Keeps the current state instead of the one computed by `pretick`, still growing older.
+    pub fn hold(&mut self) {
+        self.next_state = self.state.clone();
+        self.tick();
+    }
+
+    /// Age of the cell, when tracked.
+    pub fn age(&self) -> Option<CellAge> {
+        self.age
+    }
+
+    pub fn track_age(&mut self) {
+        self.age = Some(CellAge::of(&self.state));
+    }
+
+    /// Tracks the age of the cell from the given one on.
+    pub fn set_age(&mut self, age: CellAge) {
+        self.age = Some(age);
+    }
+
+    /// Puts the cell in another state, its age restarting from there.
+    pub fn set_state(&mut self, state: &CellState) {
+        self.state = state.clone();
+        if self.age.is_some() {
+            self.track_age();
         }
     }
 
     pub fn new(state: &CellState) -> Cell {
         Cell {
-            state: match state {
-                CellState::ALIVE => CellState::ALIVE,
-                CellState::DEAD => CellState::DEAD
-            },
+            state: state.clone(),
             next_state: CellState::ALIVE,
             neighbours: vec![],
+            age: None,
         }
     }
 
@@ -78,6 +100,7 @@ impl Cell {
             },
             next_state: CellState::ALIVE,
             neighbours: vec![],
+            age: None,
         }
     }
 
@@ -86,6 +109,7 @@ impl Cell {
             state: CellState::ALIVE,
             next_state: CellState::ALIVE,
             neighbours: vec![],
+            age: None,
         }
     }
 
@@ -94,40 +118,38 @@ impl Cell {
             state: CellState::DEAD,
             next_state: CellState::ALIVE,
             neighbours: vec![],
+            age: None,
         }
     }
 
     pub fn print(&self) -> String {
-        let print = match self.is_alive() {
-            true => { "x".to_string() }
-            false => { "o".to_string() }
-        };
-        format!("{}", print)
+        self.state.print()
     }
 
     pub fn print_neighbours_count(&self) -> String {
-        return format!("({}n)", self.neighbours.len());
+        format!("({}n)", self.neighbours.len())
     }
 
     pub fn print_neighbours_positions(&self) -> String {
         self.neighbours
             .iter()
-            .map(|(cell, position)| position.print())
+            .map(|(_, position)| position.print())
             .collect::<Vec<String>>()
             .join(",")
     }
 
-    fn count_live_neighbours(&self) -> usize {
+    fn neighbours_states(&self) -> Vec<(CellState, RelativePosition)> {
+        // On small wrapped grids a cell may be its own neighbour, and is then
+        // already borrowed while being updated
         self
             .neighbours
             .iter()
-            .map(|(cell, _)| cell.borrow().is_alive())
-            .filter(|x| *x == true)
-            .count()
+            .map(|(cell, position)| (cell.try_borrow().map_or_else(|_| self.get_state(), |cell| cell.get_state()), *position))
+            .collect()
     }
 
     fn has_neighbour_at_position(&self, requested_position: &RelativePosition) -> bool {
-        return self.neighbours.iter().any(|(_, position)| position == requested_position);
+        self.neighbours.iter().any(|(_, position)| position == requested_position)
     }
 }
 
@@ -152,7 +174,7 @@ mod cell_tests {
 
         cell.tick();
 
-        assert_eq!(cell.is_alive(), true);
+        assert!(cell.is_alive());
     }
 
     #[test]
@@ -167,11 +189,7 @@ mod cell_tests {
         let east_neighbours: usize = cell
             .neighbours
             .into_iter()
-            .filter(|(_, position)| match position {
-                RelativePosition::East => { true }
-                _ => { false }
-            })
-            .map(|(cell, _)| cell)
+            .filter(|(_, position)| matches!(position, RelativePosition::East))
             .count();
         assert_eq!(east_neighbours, 1);
     }
@@ -206,7 +224,7 @@ mod cell_tests {
             central.borrow_mut().pretick();
             central.borrow_mut().tick();
 
-            assert_eq!(central.borrow().is_alive(), false);
+            assert!(!central.borrow().is_alive());
         }
 
         // Any live cell with two or three live neighbours lives on to the next generation.
@@ -233,7 +251,7 @@ mod cell_tests {
             central.pretick();
             central.tick();
 
-            assert_eq!(central.is_alive(), true);
+            assert!(central.is_alive());
         }
 
         // Any live cell with more than three live neighbours dies, as if by overcrowding.
@@ -260,7 +278,7 @@ mod cell_tests {
             central.pretick();
             central.tick();
 
-            assert_eq!(central.is_alive(), false);
+            assert!(!central.is_alive());
         }
 
         // Any dead cell with exactly three live neighbours becomes a live cell, as if by reproduction.
@@ -287,7 +305,7 @@ mod cell_tests {
             central.pretick();
             central.tick();
 
-            assert_eq!(central.is_alive(), true);
+            assert!(central.is_alive());
         }
     }
 }
\ No newline at end of file
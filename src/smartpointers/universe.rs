@@ -1,10 +1,26 @@
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
+use std::ops::Deref;
 use std::rc::Rc;
 
-use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
+use crate::common::active_region::ActiveRegion;
+use crate::common::cell_age::CellAge;
 use crate::common::cell_state::CellState;
+use crate::common::history::{GenerationDelta, History};
+use crate::common::neighbourhood::Neighbourhood;
 use crate::common::relative_position::RelativePosition;
+use crate::common::resize::{self, Anchor};
+use crate::common::rule::{LifeLikeRule, Rule};
+use crate::common::simulation::Simulation;
+use crate::common::snapshot::{Noise, RngState, Snapshot};
+use crate::common::stamp::Stamp;
+use crate::common::statistics::GenerationStatistics;
+use crate::common::topology::{Location, Topology};
+use crate::common::update_mode::UpdateMode;
 use crate::smartpointers::cell::Cell;
 
 static UNIVERSE_START_INDEX: usize = 0;
@@ -16,19 +32,71 @@ struct CellPosition {
     cell: Rc<RefCell<Cell>>,
 }
 
-#[derive(Debug, PartialEq)]
-pub struct Universe {
+// Neighbours hold each other, so that cells are only freed once their links
+// are cleared when the grid is dropped
+#[derive(Debug)]
+struct Grid {
     width: usize,
     height: usize,
     cells: Vec<Vec<CellPosition>>,
 }
 
+impl Deref for Grid {
+    type Target = Vec<Vec<CellPosition>>;
+
+    fn deref(&self) -> &Vec<Vec<CellPosition>> {
+        &self.cells
+    }
+}
+
+impl Drop for Grid {
+    fn drop(&mut self) {
+        for cell_position in self.cells.iter().flatten() {
+            cell_position.cell.borrow_mut().clear_neighbours();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+struct TickCounters {
+    generation: usize,
+    births: usize,
+    deaths: usize,
+}
+
+#[derive(Debug)]
+pub struct Universe {
+    // Replaced when the universe grows at a tick
+    cells: RefCell<Grid>,
+    rule: Rc<dyn Rule>,
+    neighbourhood: Neighbourhood,
+    topology: Topology,
+    update_mode: UpdateMode,
+    rng: RefCell<ChaCha12Rng>,
+    counters: RefCell<TickCounters>,
+    history: RefCell<History>,
+    // Tiles to recompute at the next tick, built at the first tick that can skip any
+    active_region: RefCell<Option<ActiveRegion>>,
+    // Cells added beyond an edge alive cells come close to, before every tick
+    expansion: Option<usize>,
+}
+
 impl Universe {
     pub fn new(width: usize, height: usize) -> Universe {
-        let states = Self::generate_base_states(width, height);
+        let states = Self::generate_base_states(width, height, &mut rand::thread_rng());
         Universe::new_with_defined_states(states)
     }
 
+    /// Random universe drawn from the seed, which then drives its updates as well.
+    pub fn new_with_seed(width: usize, height: usize, seed: u64) -> Universe {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let states = Self::generate_base_states(width, height, &mut rng);
+        Universe {
+            rng: RefCell::new(rng),
+            ..Universe::new_with_defined_states(states)
+        }
+    }
+
     pub fn new_with_defined_states(states: Vec<Vec<CellState>>) -> Universe {
         let height = states.len();
         let width = states[0].len();
@@ -51,8 +119,6 @@ impl Universe {
                 };
                 let cell = Rc::new(RefCell::new(cell));
 
-                Self::add_neighbours(width, height, &mut cells, &mut line, y, x, &cell);
-
                 line.push(CellPosition {
                     x,
                     y,
@@ -63,29 +129,466 @@ impl Universe {
             cells.push(line);
         }
 
+        let neighbourhood = Neighbourhood::default();
+        let topology = Topology::default();
+        Self::link_neighbours(&cells, &neighbourhood, &topology);
+
+        Universe {
+            cells: RefCell::new(Grid { width, height, cells }),
+            rule: Rc::new(LifeLikeRule::conway()),
+            neighbourhood,
+            topology,
+            update_mode: UpdateMode::default(),
+            rng: RefCell::new(ChaCha12Rng::from_entropy()),
+            counters: RefCell::new(TickCounters::default()),
+            history: RefCell::new(History::default()),
+            active_region: RefCell::new(None),
+            expansion: None,
+        }
+    }
+
+    /// Universe as it was saved, going on from where its random number
+    /// generators were and able to step back through its history.
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Universe, String> {
+        let (rule, neighbourhood, topology, update_mode) = snapshot.settings()?;
+        if snapshot.previous_states.is_some() {
+            return Err(String::from("Second-order universes only run on the no pointer engine"));
+        }
+        let universe = Universe::new_with_defined_states(snapshot.cells)
+            .with_rule(rule)
+            .with_neighbourhood(neighbourhood)
+            .with_topology(topology)
+            .with_update_mode(update_mode);
+        if let Some(ages) = snapshot.ages {
+            for (cell_position, age) in universe.cells.borrow().iter().flatten().zip(ages.into_iter().flatten()) {
+                cell_position.cell.borrow_mut().set_age(age);
+            }
+        }
+        *universe.counters.borrow_mut() = TickCounters {
+            generation: snapshot.generation,
+            births: snapshot.births,
+            deaths: snapshot.deaths,
+        };
+
+        Ok(Universe {
+            rng: RefCell::new(snapshot.rng.restore()),
+            history: RefCell::new(snapshot.history),
+            expansion: snapshot.expansion,
+            ..universe
+        })
+    }
+
+    /// Full state of the universe, to be saved and resumed later, random
+    /// number generators and history included.
+    pub fn snapshot(&self) -> Snapshot {
+        let counters = self.counters.borrow();
+        Snapshot {
+            width: self.width(),
+            height: self.height(),
+            rule: self.rule.name(),
+            neighbourhood: self.neighbourhood.to_string(),
+            topology: self.topology.to_string(),
+            update_mode: self.update_mode.to_string(),
+            generation: counters.generation,
+            births: counters.births,
+            deaths: counters.deaths,
+            rng: RngState::of(&self.rng.borrow()),
+            noise: self.rule.as_stochastic().map(Noise::of),
+            history: self.history.borrow().clone(),
+            expansion: self.expansion,
+            cells: self.states(),
+            ages: self.ages(),
+            previous_states: None,
+        }
+    }
+
+    /// Uses the rule, along with its neighbourhood when it is defined on one.
+    pub fn with_rule(self, rule: Rc<dyn Rule>) -> Universe {
+        let universe = match rule.neighbourhood() {
+            Some(neighbourhood) => self.with_neighbourhood(neighbourhood),
+            None => self,
+        };
+        Universe {
+            rule,
+            active_region: RefCell::new(None),
+            ..universe
+        }
+    }
+
+    pub fn rule(&self) -> Rc<dyn Rule> {
+        Rc::clone(&self.rule)
+    }
+
+    /// Links every cell to the ones of the new neighbourhood instead of the previous ones.
+    pub fn with_neighbourhood(self, neighbourhood: Neighbourhood) -> Universe {
+        Self::link_neighbours(&self.cells.borrow(), &neighbourhood, &self.topology);
+        Universe {
+            neighbourhood,
+            active_region: RefCell::new(None),
+            ..self
+        }
+    }
+
+    pub fn neighbourhood(&self) -> &Neighbourhood {
+        &self.neighbourhood
+    }
+
+    /// Links the cells along the edges to the ones the topology joins them to.
+    /// Spheres need a square universe, as `Topology::check_size` tells.
+    pub fn with_topology(self, topology: Topology) -> Universe {
+        Self::link_neighbours(&self.cells.borrow(), &self.neighbourhood, &topology);
+        Universe {
+            topology,
+            active_region: RefCell::new(None),
+            ..self
+        }
+    }
+
+    pub fn topology(&self) -> &Topology {
+        &self.topology
+    }
+
+    pub fn with_update_mode(self, update_mode: UpdateMode) -> Universe {
+        Universe {
+            update_mode,
+            active_region: RefCell::new(None),
+            ..self
+        }
+    }
+
+    pub fn update_mode(&self) -> &UpdateMode {
+        &self.update_mode
+    }
+
+    /// Tracks how long every cell has been alive or dead from now on.
+    pub fn with_age_tracking(self) -> Universe {
+        for cell_position in self.cells.borrow().iter().flatten() {
+            cell_position.cell.borrow_mut().track_age();
+        }
+        self
+    }
+
+    /// Ages of the cells, when tracked.
+    pub fn ages(&self) -> Option<Vec<Vec<CellAge>>> {
+        self
+            .cells
+            .borrow()
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|cell_position| cell_position.cell.borrow().age())
+                .collect()
+            )
+            .collect()
+    }
+
+    pub fn age_of(&self, x: usize, y: usize) -> Option<CellAge> {
+        self.cells.borrow().get(y)?.get(x)?.cell.borrow().age()
+    }
+
+    /// Draws the same update order and updated cells at every run with the same seed.
+    pub fn with_seed(self, seed: u64) -> Universe {
         Universe {
-            width,
-            height,
-            cells,
+            rng: RefCell::new(ChaCha12Rng::seed_from_u64(seed)),
+            ..self
         }
     }
 
+    /// Keeps the changes of the last ticks, up to the limit, to step back through them.
+    pub fn with_history(self, limit: usize) -> Universe {
+        Universe {
+            history: RefCell::new(History::new(limit)),
+            ..self
+        }
+    }
+
+    /// Grows the universe before every tick by at least the given number of
+    /// cells beyond any edge alive cells are within reach of, as if it had
+    /// none. Stepping back keeps the universe at its larger size.
+    pub fn with_auto_expand(self, cells: usize) -> Universe {
+        Universe {
+            expansion: Some(cells),
+            ..self
+        }
+    }
+
+    /// Changes the size of the universe, the anchor telling which part of it
+    /// stays in place. Cells are added dead or cut on the other sides, every
+    /// cell being linked again to its neighbours, and the history follows
+    /// the cells that are kept.
+    pub fn resize(&mut self, width: usize, height: usize, anchor: Anchor) -> Result<(), String> {
+        if width == 0 || height == 0 {
+            return Err(format!("Cannot resize the universe to {}x{}, it needs at least one cell", width, height));
+        }
+        self.topology.check_size(width, height)?;
+        let offset = anchor.offset((self.width(), self.height()), (width, height));
+        self.move_cells(width, height, offset);
+        Ok(())
+    }
+
+    fn move_cells(&self, width: usize, height: usize, offset: (i64, i64)) {
+        let mut grid = self.cells.borrow_mut();
+        // Cells cut off are unlinked, so that they are freed once dropped, the
+        // other ones being linked again below
+        for cell_position in grid.iter().flatten() {
+            cell_position.cell.borrow_mut().clear_neighbours();
+        }
+        // New cells have their age tracked when the other ones do
+        let tracks_ages = grid[0][0].cell.borrow().age().is_some();
+        let cells = resize::moved(std::mem::take(&mut grid.cells), (width, height), offset, |x, y, former| CellPosition {
+            x,
+            y,
+            cell: former.map_or_else(
+                || {
+                    let mut cell = Cell::new_dead();
+                    if tracks_ages {
+                        cell.track_age();
+                    }
+                    Rc::new(RefCell::new(cell))
+                },
+                |former| former.cell,
+            ),
+        });
+        *grid = Grid { width, height, cells };
+        Self::link_neighbours(&grid, &self.neighbourhood, &self.topology);
+        self.history.borrow_mut().translate(offset, (width, height));
+        *self.active_region.borrow_mut() = None;
+    }
+
+    // Grows the universe beyond the edges alive cells are within reach of, so
+    // that the next generation has room for the cells they may give birth to
+    fn expand_towards_alive_cells(&self) {
+        let Some(expansion) = self.expansion else {
+            return;
+        };
+        let alive = self.iter_live().map(|(x, y, _)| (x, y));
+        if let Some(((width, height), offset)) = resize::expansion(alive, (self.width(), self.height()), self.neighbourhood.range(), expansion, &self.topology) {
+            self.move_cells(width, height, offset);
+        }
+    }
+
+    pub fn history(&self) -> Ref<'_, History> {
+        self.history.borrow()
+    }
+
+    /// Puts the universe back as it was one tick ago, taking the changes from the history.
+    pub fn step_back(&self) -> Result<(), String> {
+        let delta = self.history.borrow_mut().undo().ok_or("No generation left in the history to step back to")?;
+        let mut states = self.states();
+        delta.restore(&mut states);
+
+        for (cell_position, state) in self.cells.borrow().iter().flatten().zip(states.iter().flatten()) {
+            cell_position.cell.borrow_mut().set_state(state);
+        }
+        if let Some(active_region) = self.active_region.borrow_mut().as_mut() {
+            active_region.mark_all_changed();
+        }
+        *self.counters.borrow_mut() = TickCounters {
+            generation: delta.generation,
+            births: delta.births,
+            deaths: delta.deaths,
+        };
+        Ok(())
+    }
+
+    /// Ticks forward or steps back through the history up to the given generation.
+    pub fn goto(&self, generation: usize) -> Result<(), String> {
+        if generation < self.generation() && self.history.borrow().earliest_generation().is_none_or(|earliest| generation < earliest) {
+            return Err(format!("Generation {} is no longer in the history", generation));
+        }
+
+        while self.generation() > generation {
+            self.step_back()?;
+        }
+        while self.generation() < generation {
+            self.tick();
+        }
+        Ok(())
+    }
+
     pub fn tick(&self) {
-        for c_x in &self.cells {
-            for c_y in c_x {
-                c_y.cell.borrow_mut().pretick();
+        self.expand_towards_alive_cells();
+        let states_before_tick = match self.history.borrow().is_recording() {
+            true => Some((self.statistics(), self.states())),
+            false => None,
+        };
+        self.tick_cells();
+
+        if let Some((statistics, states)) = states_before_tick {
+            let delta = GenerationDelta::between(statistics.generation, statistics.births, statistics.deaths, &states, &self.states());
+            self.history.borrow_mut().record(delta);
+        }
+    }
+
+    fn tick_cells(&self) {
+        let grid = self.cells.borrow();
+        let mut cells = grid.iter().flatten().collect::<Vec<&CellPosition>>();
+        let mut births = 0;
+        let mut deaths = 0;
+        let mut update = |cell: &mut Cell| {
+            let was_alive = cell.is_alive();
+            cell.tick();
+            match (was_alive, cell.is_alive()) {
+                (false, true) => births += 1,
+                (true, false) => deaths += 1,
+                _ => {}
             }
+        };
+
+        match self.update_mode {
+            // Every cell sees the states its neighbours took before it in the tick
+            UpdateMode::RandomOrder => {
+                cells.shuffle(&mut *self.rng.borrow_mut());
+                for cell_position in cells {
+                    let mut cell = cell_position.cell.borrow_mut();
+                    cell.pretick_with_rule(self.rule.as_ref());
+                    update(&mut cell);
+                }
+            }
+            // Only the cells of the active region are computed, the other ones
+            // keeping their state, when the rule and the update mode allow it
+            _ => {
+                let mut active_region = ActiveRegion::can_skip(self.rule.as_ref(), &self.update_mode)
+                    .then(|| ActiveRegion::resume(self.active_region.take(), self.width(), self.height(), &self.neighbourhood, &self.topology));
+                let active_cells = cells
+                    .iter()
+                    .map(|cell_position| active_region.as_ref().is_none_or(|active_region| active_region.is_active(cell_position.x, cell_position.y)))
+                    .collect::<Vec<bool>>();
+
+                for (cell_position, _) in cells.iter().zip(&active_cells).filter(|(_, active)| **active) {
+                    cell_position.cell.borrow_mut().pretick_with_rule(self.rule.as_ref());
+                }
+                for (cell_position, active) in cells.iter().zip(active_cells) {
+                    let mut cell = cell_position.cell.borrow_mut();
+                    if self.updates_now() && active {
+                        let state = cell.get_state();
+                        update(&mut cell);
+                        if let Some(active_region) = active_region.as_mut() {
+                            active_region.record(cell_position.x, cell_position.y, &state, &cell.get_state());
+                        }
+                    } else {
+                        cell.hold();
+                    }
+                }
+                *self.active_region.borrow_mut() = active_region;
+            }
+        }
+
+        let mut counters = self.counters.borrow_mut();
+        counters.generation += 1;
+        counters.births = births;
+        counters.deaths = deaths;
+    }
+
+    fn updates_now(&self) -> bool {
+        match self.update_mode {
+            UpdateMode::Probabilistic(probability) => self.rng.borrow_mut().gen_bool(probability),
+            _ => true,
         }
-        for c_x in &self.cells {
-            for c_y in c_x {
-                c_y.cell.borrow_mut().tick();
+    }
+
+    pub fn states(&self) -> Vec<Vec<CellState>> {
+        self
+            .cells
+            .borrow()
+            .iter()
+            .map(|line| line
+                .iter()
+                .map(|cell_position| cell_position.cell.borrow().get_state())
+                .collect()
+            )
+            .collect()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.borrow().width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.borrow().height
+    }
+
+    pub fn generation(&self) -> usize {
+        self.counters.borrow().generation
+    }
+
+    pub fn statistics(&self) -> GenerationStatistics {
+        let counters = self.counters.borrow();
+        GenerationStatistics::from_states(counters.generation, counters.births, counters.deaths, &self.states())
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<CellState> {
+        Some(self.cells.borrow().get(y)?.get(x)?.cell.borrow().get_state())
+    }
+
+    /// Changes a cell between ticks, its links to its neighbours staying as they
+    /// are, while the history and statistics are left as they were.
+    pub fn set(&self, x: usize, y: usize, state: CellState) -> Result<(), String> {
+        let grid = self.cells.borrow();
+        let cell_position = grid
+            .get(y)
+            .and_then(|line| line.get(x))
+            .ok_or(format!("Cell ({}, {}) is outside of the {}x{} universe", x, y, grid.width, grid.height))?;
+        cell_position.cell.borrow_mut().set_state(&state);
+        self.mark_changed(x, y);
+        Ok(())
+    }
+
+    pub fn toggle(&self, x: usize, y: usize) -> Result<(), String> {
+        let state = self.get(x, y).map_or(CellState::DEAD, |state| state.toggled());
+        self.set(x, y, state)
+    }
+
+    pub fn clear(&self) {
+        self.fill_rect(0, 0, self.width(), self.height(), CellState::DEAD);
+    }
+
+    /// Sets every cell of the rectangle, the part of it outside of the universe being left out.
+    pub fn fill_rect(&self, x: usize, y: usize, width: usize, height: usize, state: CellState) {
+        for line in self.cells.borrow().iter().skip(y).take(height) {
+            for cell_position in line.iter().skip(x).take(width) {
+                cell_position.cell.borrow_mut().set_state(&state);
+                self.mark_changed(cell_position.x, cell_position.y);
             }
         }
     }
 
+    /// Places a pattern into the universe between ticks, as `set` would do cell by cell.
+    pub fn stamp(&self, stamp: &Stamp) {
+        for (x, y, state) in stamp.changes(&self.states()) {
+            self.cells.borrow()[y][x].cell.borrow_mut().set_state(&state);
+            self.mark_changed(x, y);
+        }
+    }
+
+    fn mark_changed(&self, x: usize, y: usize) {
+        if let Some(active_region) = self.active_region.borrow_mut().as_mut() {
+            active_region.mark_changed(x, y);
+        }
+    }
+
+    /// Tiles computed at the last tick, when cells whose neighbourhood did not change are skipped.
+    pub fn active_region(&self) -> Ref<'_, Option<ActiveRegion>> {
+        self.active_region.borrow()
+    }
+
+    /// Coordinates and states of the alive cells, line by line.
+    pub fn iter_live(&self) -> impl Iterator<Item=(usize, usize, CellState)> + '_ {
+        self
+            .cells
+            .borrow()
+            .iter()
+            .flatten()
+            .map(|cell_position| (cell_position.x, cell_position.y, cell_position.cell.borrow().get_state()))
+            .filter(|(_, _, state)| state.is_alive())
+            .collect::<Vec<(usize, usize, CellState)>>()
+            .into_iter()
+    }
+
     pub fn print(&self) -> Vec<String> {
         self
             .cells
+            .borrow()
             .iter()
             .map(|x| x
                 .iter()
@@ -97,9 +600,11 @@ impl Universe {
             .collect()
     }
 
+    #[cfg(test)]
     fn print_check(&self) -> Vec<String> {
         self
             .cells
+            .borrow()
             .iter()
             .map(|x| x
                 .iter()
@@ -110,13 +615,13 @@ impl Universe {
             .collect()
     }
 
-    fn generate_base_states(width: usize, height: usize) -> Vec<Vec<CellState>> {
+    fn generate_base_states(width: usize, height: usize, rng: &mut impl Rng) -> Vec<Vec<CellState>> {
         let mut states: Vec<Vec<CellState>> = vec![];
 
         for _ in 0..height {
             let mut line_states: Vec<CellState> = vec![];
             for _ in 0..width {
-                let rand = rand::thread_rng().gen_range(0..2);
+                let rand = rng.gen_range(0..2);
                 let state = match rand {
                     0 => CellState::DEAD,
                     _ => CellState::ALIVE,
@@ -129,65 +634,84 @@ impl Universe {
         states
     }
 
-    fn add_neighbours(
-        width: usize,
-        height: usize,
-        cells: &mut Vec<Vec<CellPosition>>,
-        line: &mut Vec<CellPosition>,
-        current_cell_y_position: usize,
-        current_cell_x_position: usize,
-        cell: &Rc<RefCell<Cell>>,
-    ) {
-        let column_neighbours_start = if current_cell_y_position > 0 { current_cell_y_position - 1 } else { 0 };
-        for q in column_neighbours_start..=current_cell_y_position + 1 {
-            if q < height {
-                let line_neighbours_start = if current_cell_x_position > 0 { current_cell_x_position - 1 } else { 0 };
-                for p in line_neighbours_start..=current_cell_x_position + 1 {
-                    if p < width {
-                        if q == current_cell_y_position { // Si on est sur la ligne en train d'être remplie
-                            Self::add_neighbours_for_current_line(line, current_cell_y_position, current_cell_x_position, &cell, q, p);
-                        } else {
-                            Self::add_neighbours_for_existing_lines(width, cells, current_cell_y_position, current_cell_x_position, &cell, q);
-                        }
-                    }
+    fn link_neighbours(cells: &[Vec<CellPosition>], neighbourhood: &Neighbourhood, topology: &Topology) {
+        let offsets = neighbourhood.offsets();
+        let height = cells.len();
+        let width = cells.first().map_or(0, |line| line.len());
+        // A single cell, never ticked, stands for the whole border
+        let mut border: Option<Rc<RefCell<Cell>>> = None;
+
+        for line in cells {
+            for cell_position in line {
+                let mut cell = cell_position.cell.borrow_mut();
+                cell.clear_neighbours();
+
+                for (offset_x, offset_y) in &offsets {
+                    let neighbour_x = cell_position.x as i64 + *offset_x as i64;
+                    let neighbour_y = cell_position.y as i64 + *offset_y as i64;
+                    let neighbour = match topology.locate(neighbour_x, neighbour_y, width, height) {
+                        Location::Cell(x, y) => Rc::clone(&cells[y][x].cell),
+                        Location::Border(state) => Rc::clone(border.get_or_insert_with(|| Rc::new(RefCell::new(Cell::new(&state))))),
+                        Location::Outside => continue,
+                    };
+                    cell.add_neighbour(neighbour, RelativePosition::from_offset(*offset_x, *offset_y));
                 }
             }
         }
     }
+}
 
-    fn add_neighbours_for_existing_lines(width: usize, cells: &mut Vec<Vec<CellPosition>>, current_cell_y_position: usize, current_cell_x_position: usize, cell: &&Rc<RefCell<Cell>>, q: usize) {
-        match cells.get(q) {
-            Some(current_line) => {
-                let line_neighbours_start = if current_cell_x_position > 0 { current_cell_x_position - 1 } else { 0 };
-                for p in line_neighbours_start..=current_cell_x_position + 1 {
-                    if p < width {
-                        match current_line.get(p) {
-                            Some(current_neighbour) => {
-                                cell.borrow_mut().add_neighbour(Rc::clone(&current_neighbour.cell), RelativePosition::get_position_from(current_cell_x_position, current_cell_y_position, p, q));
-                                current_neighbour.cell.borrow_mut().add_neighbour(Rc::clone(&cell), RelativePosition::get_position_from(p, q, current_cell_x_position, current_cell_y_position));
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-            }
-            _ => {}
-        }
+impl Serialize for Universe {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Universe {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Universe, D::Error> {
+        Universe::from_snapshot(Snapshot::deserialize(deserializer)?).map_err(de::Error::custom)
     }
+}
 
-    fn add_neighbours_for_current_line(line: &mut Vec<CellPosition>, current_cell_y_position: usize, current_cell_x_position: usize, cell: &&Rc<RefCell<Cell>>, q: usize, p: usize) {
-        match line.get(p) {
-            Some(current_neighbour) => {
-                cell.borrow_mut().add_neighbour(Rc::clone(&current_neighbour.cell), RelativePosition::get_position_from(current_cell_x_position, current_cell_y_position, p, q));
-                current_neighbour.cell.borrow_mut().add_neighbour(Rc::clone(&cell), RelativePosition::get_position_from(p, q, current_cell_x_position, current_cell_y_position));
-            }
-            _ => {}
-        }
+impl Simulation for Universe {
+    fn advance(&mut self) {
+        self.tick();
+    }
+
+    fn states(&self) -> Vec<Vec<CellState>> {
+        Universe::states(self)
+    }
+
+    fn statistics(&self) -> GenerationStatistics {
+        Universe::statistics(self)
+    }
+
+    fn print(&self) -> Vec<String> {
+        Universe::print(self)
+    }
+
+    fn ages(&self) -> Option<Vec<Vec<CellAge>>> {
+        Universe::ages(self)
     }
 }
 
 #[cfg(test)]
 mod universe_tests {
+    use crate::common::cell_age::CellAge;
+    use crate::common::cell_state::CellState;
+    use std::rc::Rc;
+
+    use crate::common::neighbourhood::Neighbourhood;
+    use crate::common::catalogue;
+    use crate::common::resize::Anchor;
+    use crate::common::rule::LifeLikeRule;
+    use crate::common::snapshot;
+    use crate::common::stamp::{CombineMode, Stamp};
+    use crate::common::statistics::BoundingBox;
+    use crate::common::topology::Topology;
+    use crate::common::transform::Transform;
+    use crate::common::update_mode::UpdateMode;
+    use crate::nopointer::universe as np;
     use crate::smartpointers::universe::Universe;
 
     #[test]
@@ -195,7 +719,7 @@ mod universe_tests {
         let universe = Universe::new(1, 1);
 
         for line_to_print in universe.print() {
-            assert_eq!(line_to_print, "x");
+            assert!(line_to_print == "x" || line_to_print == "o");
             println!("{:?}", line_to_print);
         }
     }
@@ -282,13 +806,252 @@ mod universe_tests {
         print_universe(&universe);
 
         let number_of_ticks = 10;
-        for x in 0..=number_of_ticks {
+        for _ in 0..=number_of_ticks {
             println!("Tick");
             universe.tick();
             print_universe(&universe);
         }
     }
 
+    #[test]
+    fn should_report_statistics_of_the_last_tick() {
+        let universe = Universe::new_with_defined_states(vec![
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD],
+        ]);
+
+        universe.tick();
+        let statistics = universe.statistics();
+
+        assert_eq!(statistics.generation, 1);
+        assert_eq!(statistics.population, 3);
+        assert_eq!(statistics.births, 2);
+        assert_eq!(statistics.deaths, 2);
+        assert_eq!(statistics.bounding_box, Some(BoundingBox { min_x: 0, min_y: 1, max_x: 2, max_y: 1 }));
+    }
+
+    #[test]
+    fn should_show_decaying_cells_with_a_generations_rule() {
+        let universe = Universe::new_with_defined_states(vec![
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD, CellState::DEAD],
+            vec![CellState::DEAD, CellState::ALIVE, CellState::ALIVE, CellState::DEAD],
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD, CellState::DEAD],
+        ]).with_rule(Rc::new(LifeLikeRule::parse("B2/S/C3").unwrap()));
+
+        universe.tick();
+        assert_eq!(universe.print(), vec!["o x x o", "o 1 1 o", "o x x o"]);
+
+        universe.tick();
+        assert_eq!(universe.print()[1], "x o o x");
+    }
+
+    #[test]
+    fn should_link_cells_to_selected_neighbourhood() {
+        let universe = Universe::new(3, 3);
+
+        let von_neumann = universe.with_neighbourhood(Neighbourhood::VonNeumann(1));
+        assert_eq!(von_neumann.print_check()[1], "(10)((3n):N,E,S) (11)((4n):N,W,E,S) (12)((3n):N,W,S)");
+
+        let hexagonal = von_neumann.with_neighbourhood(Neighbourhood::Hexagonal);
+        assert_eq!(hexagonal.print_check()[1], "(10)((4n):N,E,S,SE) (11)((6n):NW,N,W,E,S,SE) (12)((4n):NW,N,W,S)");
+
+        let extended = hexagonal.with_neighbourhood(Neighbourhood::Moore(2));
+        assert_eq!(extended.print_check()[0], "(00)((8n):E,x+2y+0,S,SE,x+2y+1,x+0y+2,x+1y+2,x+2y+2) (01)((8n):W,E,SW,S,SE,x-1y+2,x+0y+2,x+1y+2) (02)((8n):x-2y+0,W,x-2y+1,SW,S,x-2y+2,x-1y+2,x+0y+2)");
+    }
+
+    #[test]
+    fn should_link_edge_cells_to_the_ones_the_topology_joins_them_to() {
+        let universe = Universe::new(3, 3).with_topology(Topology::Torus);
+        assert_eq!(universe.print_check()[0], "(00)((8n):NW,N,NE,W,E,SW,S,SE) (01)((8n):NW,N,NE,W,E,SW,S,SE) (02)((8n):NW,N,NE,W,E,SW,S,SE)");
+
+        let bordered = universe.with_topology(Topology::Bordered(CellState::DEAD));
+        assert_eq!(bordered.print_check()[0], "(00)((8n):NW,N,NE,W,E,SW,S,SE) (01)((8n):NW,N,NE,W,E,SW,S,SE) (02)((8n):NW,N,NE,W,E,SW,S,SE)");
+
+        let plane = bordered.with_topology(Topology::Plane);
+        assert_eq!(plane.print_check()[0], "(00)((3n):E,S,SE) (01)((5n):W,E,SW,S,SE) (02)((3n):W,SW,S)");
+    }
+
+    #[test]
+    fn should_track_ages_of_cells_on_demand() {
+        let universe = Universe::new_with_defined_states(vec![
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD],
+            vec![CellState::ALIVE, CellState::ALIVE, CellState::ALIVE],
+            vec![CellState::DEAD, CellState::DEAD, CellState::DEAD],
+        ]);
+        assert_eq!(universe.ages(), None);
+
+        let universe = universe.with_age_tracking();
+        universe.tick();
+        universe.tick();
+
+        assert_eq!(universe.age_of(1, 1), Some(CellAge::Alive(3)));
+        assert_eq!(universe.age_of(0, 1), Some(CellAge::Alive(1)));
+        assert_eq!(universe.age_of(1, 0), Some(CellAge::Dead(1)));
+        assert_eq!(universe.age_of(0, 0), Some(CellAge::NeverAlive));
+    }
+
+    #[test]
+    fn should_keep_cells_that_are_not_updated() {
+        let universe = Universe::new(8, 8).with_update_mode(UpdateMode::Probabilistic(0.0));
+        let states = universe.states();
+
+        universe.tick();
+
+        assert_eq!(universe.states(), states);
+        assert_eq!(universe.generation(), 1);
+    }
+
+    #[test]
+    fn should_tick_a_cell_that_is_its_own_neighbour() {
+        let universe = Universe::new_with_defined_states(vec![vec![CellState::ALIVE]]).with_topology(Topology::Torus);
+
+        universe.tick();
+
+        assert_eq!(universe.print(), vec!["o"]);
+    }
+
+    #[test]
+    fn should_step_back_through_the_history() {
+        let universe = Universe::new_with_seed(10, 10, 4).with_history(5);
+        let first = universe.states();
+        universe.tick();
+        universe.tick();
+        let second = universe.statistics();
+        universe.tick();
+
+        universe.step_back().unwrap();
+
+        assert_eq!(universe.statistics(), second);
+        assert_eq!(universe.history().len(), 2);
+        universe.goto(0).unwrap();
+        assert_eq!(universe.states(), first);
+    }
+
+    #[test]
+    fn should_not_go_back_beyond_the_history_limit() {
+        let universe = Universe::new_with_seed(10, 10, 4).with_history(2);
+        universe.goto(5).unwrap();
+
+        assert!(universe.goto(2).is_err());
+        assert_eq!(universe.generation(), 5);
+        universe.goto(3).unwrap();
+        assert!(universe.step_back().is_err());
+    }
+
+    #[test]
+    fn should_free_cells_linked_to_each_other_once_dropped() {
+        let universe = Universe::new(3, 3).with_topology(Topology::Torus);
+        let cell = Rc::downgrade(&universe.cells.borrow()[1][1].cell);
+
+        drop(universe);
+
+        assert!(cell.upgrade().is_none());
+    }
+
+    #[test]
+    fn should_free_cells_cut_by_a_resize() {
+        let mut universe = Universe::new(4, 4).with_topology(Topology::Torus);
+        let cut = Rc::downgrade(&universe.cells.borrow()[3][3].cell);
+        let kept = Rc::downgrade(&universe.cells.borrow()[0][0].cell);
+
+        universe.resize(2, 2, Anchor::TopLeft).unwrap();
+
+        assert!(cut.upgrade().is_none());
+        assert!(kept.upgrade().is_some());
+    }
+
+    #[test]
+    fn should_edit_cells_without_breaking_links() {
+        let universe = Universe::new_with_defined_states(vec![vec![CellState::ALIVE; 5]; 5]);
+
+        universe.clear();
+        universe.fill_rect(2, 1, 1, 3, CellState::ALIVE);
+        universe.toggle(0, 0).unwrap();
+        universe.set(0, 0, CellState::DEAD).unwrap();
+        universe.tick();
+
+        assert_eq!(universe.print(), vec!["o o o o o", "o o o o o", "o x x x o", "o o o o o", "o o o o o"]);
+        assert_eq!(universe.iter_live().count(), 3);
+        assert_eq!(universe.get(1, 2), Some(CellState::ALIVE));
+        assert!(universe.toggle(5, 0).is_err());
+    }
+
+    #[test]
+    fn should_only_compute_the_tiles_around_changes() {
+        let states = vec![vec![CellState::DEAD; 64]; 64];
+        let universe = Universe::new_with_defined_states(states.clone()).with_age_tracking();
+        let computed_in_full = Universe::new_with_defined_states(states).with_age_tracking().with_update_mode(UpdateMode::Probabilistic(1.0));
+        for universe in [&universe, &computed_in_full] {
+            universe.fill_rect(40, 41, 3, 1, CellState::ALIVE);
+            universe.tick();
+            universe.tick();
+        }
+        assert_eq!(universe.active_region().as_ref().map(|active_region| active_region.active_tiles()), Some(9));
+
+        for universe in [&universe, &computed_in_full] {
+            universe.set(0, 0, CellState::ALIVE).unwrap();
+            universe.tick();
+        }
+
+        assert_eq!(universe.active_region().as_ref().map(|active_region| active_region.active_tiles()), Some(13));
+        assert_eq!(universe.print(), computed_in_full.print());
+        assert_eq!(universe.ages(), computed_in_full.ages());
+        assert_eq!(universe.statistics(), computed_in_full.statistics());
+    }
+
+    #[test]
+    fn should_link_cells_again_when_resized() {
+        let mut universe = Universe::new_with_defined_states(vec![vec![CellState::DEAD, CellState::ALIVE, CellState::DEAD]; 3]).with_topology(Topology::Torus).with_age_tracking();
+
+        universe.resize(5, 4, Anchor::Left).unwrap();
+        universe.tick();
+
+        assert_eq!(universe.print(), vec!["o o o o o", "x x x o o", "o o o o o", "o o o o o"]);
+        assert_eq!(universe.print_check()[0], "(00)((8n):NW,N,NE,W,E,SW,S,SE) (01)((8n):NW,N,NE,W,E,SW,S,SE) (02)((8n):NW,N,NE,W,E,SW,S,SE) (03)((8n):NW,N,NE,W,E,SW,S,SE) (04)((8n):NW,N,NE,W,E,SW,S,SE)");
+        assert_eq!(universe.age_of(4, 3), Some(CellAge::NeverAlive));
+        assert!(universe.resize(3, 0, Anchor::Centre).is_err());
+    }
+
+    #[test]
+    fn should_go_on_alike_once_saved_and_restored() {
+        let universe = Universe::new_with_seed(16, 16, 8)
+            .with_rule(Rc::new(LifeLikeRule::parse("B2/S/C3").unwrap()))
+            .with_update_mode(UpdateMode::Probabilistic(0.5))
+            .with_history(3)
+            .with_auto_expand(2);
+        universe.tick();
+
+        assert_eq!(universe.snapshot(), universe.snapshot());
+
+        let restored: Universe = snapshot::from_bytes(&snapshot::to_bytes(&universe).unwrap()).unwrap();
+        assert_eq!(restored.statistics(), universe.statistics());
+        assert_eq!(*restored.history(), *universe.history());
+
+        restored.tick();
+        universe.tick();
+        assert_eq!(restored.print(), universe.print());
+        assert_eq!(restored.snapshot().expansion, Some(2));
+        restored.step_back().unwrap();
+        universe.step_back().unwrap();
+        assert_eq!(restored.snapshot(), universe.snapshot());
+    }
+
+    #[test]
+    fn should_stamp_patterns_alike_in_both_engines() {
+        let glider = catalogue::pattern("glider").unwrap();
+        let universe = Universe::new_with_defined_states(vec![vec![CellState::DEAD; 8]; 8]);
+        let mut np_universe = np::Universe::new_from_cell_states(universe.states());
+
+        for (index, transform) in Transform::ALL.into_iter().enumerate() {
+            let stamp = Stamp::new(glider.clone()).at(index as i64 - 2, index as i64 % 4).with_transform(transform).with_mode(CombineMode::Xor);
+            universe.stamp(&stamp);
+            np_universe.stamp(&stamp);
+        }
+
+        assert_eq!(universe.print(), np_universe.print());
+    }
+
     fn print_universe(universe: &Universe) {
         for line_to_print in universe.print() {
             println!("{:?}", line_to_print);
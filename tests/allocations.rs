@@ -0,0 +1,56 @@
+//! Counts the bytes allocated while universes move on, through a global
+//! allocator of its own, which is why it lives in a test binary apart.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use kata_game_of_life_rs::common::topology::Topology;
+use kata_game_of_life_rs::nopointer::universe::Universe;
+
+struct CountingAllocator;
+
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocated_bytes_by(run: impl FnOnce()) -> usize {
+    let before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    run();
+    ALLOCATED_BYTES.load(Ordering::Relaxed) - before
+}
+
+#[test]
+fn should_allocate_nothing_when_stepping_in_place() {
+    for topology in [Topology::Plane, Topology::Torus] {
+        let ticks = 20;
+        let mut ticked = Universe::new_with_seed(64, 64, 1).with_topology(topology.clone());
+        let mut stepped = Universe::new_with_seed(64, 64, 1).with_topology(topology.clone());
+        // The first step allocates the second grid and the alive counts, which are then kept
+        stepped.step();
+        ticked = ticked.tick();
+
+        let stepping = allocated_bytes_by(|| {
+            for _ in 0..ticks {
+                stepped.step();
+            }
+        });
+        for _ in 0..ticks {
+            ticked = ticked.tick();
+        }
+
+        assert_eq!(stepping, 0, "{} bytes allocated by {} steps on a {:?}", stepping, ticks, topology);
+        assert_eq!(stepped.print(), ticked.print());
+    }
+}
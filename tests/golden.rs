@@ -0,0 +1,163 @@
+//! Runs every case of `tests/golden` on both engines. A case starts from a
+//! pattern of the catalogue or an RLE file next to it, and lists what is
+//! expected at given generations:
+//!
+//! ```text
+//! # Comment
+//! pattern glider            catalogue name, or file ending in .rle
+//! size 6 6
+//! topology torus            optional, plane by default
+//! rule B3/S23               optional, the rule of the pattern by default
+//! slow                      optional, only run with --ignored
+//! at 4 population 5
+//! at 4                      followed by the lines the universe prints
+//! o o o o o o
+//! ...
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use kata_game_of_life_rs::common::catalogue;
+use kata_game_of_life_rs::common::pattern::Pattern;
+use kata_game_of_life_rs::common::rule;
+use kata_game_of_life_rs::common::simulation::Simulation;
+use kata_game_of_life_rs::common::topology::Topology;
+use kata_game_of_life_rs::nopointer::universe as np;
+use kata_game_of_life_rs::smartpointers::universe as sp;
+
+type EngineRun = (&'static str, Box<dyn Simulation>);
+
+#[derive(Debug)]
+enum Expectation {
+    Population(usize),
+    Lines(Vec<String>),
+}
+
+#[derive(Debug)]
+struct GoldenCase {
+    name: String,
+    pattern: Pattern,
+    width: usize,
+    height: usize,
+    topology: Topology,
+    rule: Option<String>,
+    slow: bool,
+    expectations: Vec<(usize, Expectation)>,
+}
+
+impl GoldenCase {
+    fn read(path: &Path) -> Result<GoldenCase, String> {
+        let content = fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let mut pattern: Option<Pattern> = None;
+        let mut size: Option<(usize, usize)> = None;
+        let mut topology = Topology::default();
+        let mut rule: Option<String> = None;
+        let mut slow = false;
+        let mut expectations: Vec<(usize, Expectation)> = vec![];
+
+        for line in content.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let words = line.split_whitespace().collect::<Vec<&str>>();
+            match words.as_slice() {
+                ["pattern", name] if name.ends_with(".rle") => pattern = Some(Pattern::from_rle_file(&path.with_file_name(name).to_string_lossy())?),
+                ["pattern", name] => pattern = Some(catalogue::pattern(name)?),
+                ["size", width, height] => size = Some((Self::number(width)?, Self::number(height)?)),
+                ["topology", name] => topology = Topology::parse(name)?,
+                ["rule", name] => rule = Some(name.to_string()),
+                ["slow"] => slow = true,
+                ["at", generation, "population", population] => expectations.push((Self::number(generation)?, Expectation::Population(Self::number(population)?))),
+                ["at", generation] => expectations.push((Self::number(generation)?, Expectation::Lines(vec![]))),
+                _ => match expectations.last_mut() {
+                    Some((_, Expectation::Lines(lines))) => lines.push(line.to_string()),
+                    _ => return Err(format!("Unexpected line '{}'", line)),
+                }
+            }
+        }
+
+        let (width, height) = size.ok_or("Missing size")?;
+        expectations.sort_by_key(|(generation, _)| *generation);
+        Ok(GoldenCase {
+            name: path.file_stem().unwrap().to_string_lossy().to_string(),
+            pattern: pattern.ok_or("Missing pattern")?,
+            width,
+            height,
+            topology,
+            rule,
+            slow,
+            expectations,
+        })
+    }
+
+    fn number(word: &str) -> Result<usize, String> {
+        word.parse().map_err(|_| format!("Invalid number '{}'", word))
+    }
+
+    fn simulations(&self) -> Result<Vec<EngineRun>, String> {
+        let rule_name = self.rule.as_deref().or(self.pattern.rule()).unwrap_or("B3/S23");
+        let states = self.pattern.centered_in(self.width, self.height);
+        let np_universe = np::Universe::new_from_cell_states(states.clone()).with_rule(rule::from_name(rule_name)?).with_topology(self.topology.clone());
+        let sp_universe = sp::Universe::new_with_defined_states(states).with_rule(rule::from_name(rule_name)?).with_topology(self.topology.clone());
+
+        Ok(vec![("np", Box::new(np_universe)), ("sp", Box::new(sp_universe))])
+    }
+
+    /// Differences with the expectations, on every engine.
+    fn run(&self) -> Result<Vec<String>, String> {
+        let mut failures = vec![];
+        for (engine, mut simulation) in self.simulations()? {
+            let mut generation = 0;
+            for (expected_generation, expectation) in &self.expectations {
+                while generation < *expected_generation {
+                    simulation.advance();
+                    generation += 1;
+                }
+
+                let failure = match expectation {
+                    Expectation::Population(population) if simulation.statistics().population != *population => Some(format!("population {} instead of {}", simulation.statistics().population, population)),
+                    Expectation::Lines(lines) if &simulation.print() != lines => Some(format!("\n{}\ninstead of\n{}", simulation.print().join("\n"), lines.join("\n"))),
+                    _ => None,
+                };
+                if let Some(failure) = failure {
+                    failures.push(format!("{} on {} at generation {}: {}", self.name, engine, generation, failure));
+                }
+            }
+        }
+        Ok(failures)
+    }
+}
+
+fn golden_cases() -> Vec<GoldenCase> {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden");
+    let mut paths = fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "golden"))
+        .collect::<Vec<PathBuf>>();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| GoldenCase::read(path).unwrap_or_else(|error| panic!("{}: {}", path.display(), error)))
+        .collect()
+}
+
+fn run_golden_cases(slow: bool) {
+    let failures = golden_cases()
+        .iter()
+        .filter(|case| case.slow == slow)
+        .flat_map(|case| case.run().unwrap_or_else(|error| vec![format!("{}: {}", case.name, error)]))
+        .collect::<Vec<String>>();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn should_evolve_as_golden_cases_expect() {
+    run_golden_cases(false);
+}
+
+#[test]
+#[ignore = "large universes, run with cargo test --release -- --ignored"]
+fn should_evolve_as_slow_golden_cases_expect() {
+    run_golden_cases(true);
+}